@@ -0,0 +1,25 @@
+//! Dispatch with a catch-all: anything that isn't a known command is
+//! treated as a file to process.
+
+use rsb::prelude::*;
+
+fn main() {
+    let args = bootstrap!();
+
+    dispatch!(&args, {
+        "version" => do_version,
+        _ => do_process
+    });
+}
+
+fn do_version(_args: Args) -> i32 {
+    echo!("1.0.0");
+    0
+}
+
+fn do_process(args: Args) -> i32 {
+    for file in args.all() {
+        echo!("processing {}", file);
+    }
+    0
+}
@@ -0,0 +1,129 @@
+//! A small RSB tool exercising bootstrap, pre-dispatch and dispatch.
+//! The integration tests drive it as a subprocess.
+
+use rsb::prelude::*;
+
+fn main() {
+    let args = bootstrap!();
+    options!(&args);
+
+    if pre_dispatch!(&args, { "install" => do_install }) {
+        return;
+    }
+
+    usage!(
+        "greet"  => "greet [name]",
+        "config" => "config <file>"
+    );
+
+    dispatch!(&args, {
+        "greet"   => do_greet,
+        "stream"  => do_stream,
+        "config"  => do_config,
+        "fail"    => do_fail,
+        "confirm" => do_confirm,
+        "wipe"    => do_wipe,
+        "ask"     => do_ask,
+        "note"    => do_note,
+        "exec"    => do_exec,
+        "deploy"  => do_deploy
+    });
+}
+
+fn do_install(_args: Args) -> i32 {
+    okay!("Installed");
+    0
+}
+
+fn do_greet(args: Args) -> i32 {
+    set_var("WHO", args.get_or(1, "world"));
+    echo!("Hello, $WHO!");
+    0
+}
+
+// Reads stdin lines and prints them sorted and de-duplicated
+fn do_stream(_args: Args) -> i32 {
+    let mut input = String::new();
+    std::io::Read::read_to_string(&mut std::io::stdin(), &mut input).ok();
+    pipe!(input)
+        .inspect("input")
+        .sort()
+        .unique()
+        .inspect("sorted")
+        .each(|line| echo!(line));
+    0
+}
+
+fn do_config(args: Args) -> i32 {
+    let path = args.get(1);
+    require_file!(&path);
+    src!(&path);
+    echo!(param!("APP_NAME", default: "unnamed"));
+    0
+}
+
+fn do_fail(_args: Args) -> i32 {
+    error!("Something went wrong");
+    3
+}
+
+// confirm [yes|no] - the optional argument is the default answer
+fn do_confirm(args: Args) -> i32 {
+    if let Ok(secs) = args.get(2).parse::<u64>() {
+        let answer = confirm_timeout!("Proceed?", secs: secs, default: args.get(1) != "no");
+        echo!(if answer { "yes" } else { "no" });
+        return 0;
+    }
+    let answer = match args.get(1).as_str() {
+        "yes" => confirm!("Proceed?", default: true),
+        "no" => confirm!("Proceed?", default: false),
+        _ => confirm!("Proceed?"),
+    };
+    echo!(if answer { "yes" } else { "no" });
+    0
+}
+
+// wipe [word] - the optional argument replaces "yes" as the word to type
+fn do_wipe(args: Args) -> i32 {
+    let proceed = match args.get(1).as_str() {
+        "" => confirm_destructive!("Delete everything?"),
+        word => confirm_destructive!("Delete everything?", expected: word),
+    };
+    echo!(if proceed { "wiped" } else { "kept" });
+    0
+}
+
+fn do_ask(args: Args) -> i32 {
+    let secs: u64 = args.get_or(1, "1").parse().unwrap_or(1);
+    let answer = readline!("Continue? ", timeout: secs, default: "y");
+    echo!("{}", answer);
+    0
+}
+
+fn do_note(args: Args) -> i32 {
+    let text = match args.get(1).as_str() {
+        "" => prompt_multiline!("Enter a note, end with '.':", editor),
+        sentinel => prompt_multiline!("Enter a note:", until: sentinel),
+    };
+    echo!("{} lines", text.lines().count());
+    echo!(text);
+    0
+}
+
+fn do_exec(args: Args) -> i32 {
+    let argv = args.all();
+    match argv.len() {
+        0 => exec!("true"),
+        _ => exec!(argv[0], args: argv[1..]),
+    }
+}
+
+// deploy <dir> [--dry-run] - side effects that dry-run should skip
+fn do_deploy(args: Args) -> i32 {
+    let dir = args.get(1);
+    run!(format!("touch {}/ran", dir));
+    write_file(&format!("{}/written", dir), "x");
+    cp!(format!("{}/written", dir), format!("{}/copied", dir));
+    echo!("deployed");
+    0
+}
@@ -0,0 +1,191 @@
+//! Bash-style argument handling for dispatch handlers.
+
+use crate::context::{expand_vars, get_var, set_var};
+use std::collections::HashSet;
+
+// Argument list with consumption tracking, like shifting in bash
+#[derive(Debug, Clone, Default)]
+pub struct Args {
+    args: Vec<String>,
+    processed: HashSet<usize>,
+}
+
+impl Args {
+    pub fn new(args: &[String]) -> Self {
+        Args {
+            args: args.to_vec(),
+            processed: HashSet::new(),
+        }
+    }
+
+    // Get positional arg like $1, $2 in bash (1-indexed, skips consumed args)
+    pub fn get(&self, n: usize) -> String {
+        if n == 0 {
+            return get_var("SCRIPT_NAME");
+        }
+        self.args
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.processed.contains(i))
+            .nth(n - 1)
+            .map(|(_, a)| a.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn get_or(&self, n: usize, default: &str) -> String {
+        let val = self.get(n);
+        if val.is_empty() {
+            default.to_string()
+        } else {
+            val
+        }
+    }
+
+    pub fn has(&self, flag: &str) -> bool {
+        self.args.iter().any(|arg| arg == flag)
+    }
+
+    // Pop flag from args (marks as processed)
+    pub fn has_pop(&mut self, flag: &str) -> bool {
+        if let Some(pos) = self.args.iter().position(|arg| arg == flag) {
+            self.processed.insert(pos);
+            true
+        } else {
+            false
+        }
+    }
+
+    // Get flag value: --flag value or --flag=value
+    pub fn has_val(&mut self, flag: &str) -> Option<String> {
+        let prefix = format!("{}=", flag);
+        for (i, arg) in self.args.iter().enumerate() {
+            if let Some(value) = arg.strip_prefix(&prefix) {
+                self.processed.insert(i);
+                return Some(value.to_string());
+            }
+        }
+
+        if let Some(pos) = self.args.iter().position(|arg| arg == flag) {
+            if pos + 1 < self.args.len() {
+                self.processed.insert(pos);
+                self.processed.insert(pos + 1);
+                return Some(self.args[pos + 1].clone());
+            }
+        }
+
+        None
+    }
+
+    // Parse key:value or key=value arguments
+    pub fn get_kv(&mut self, key: &str) -> Option<String> {
+        for (i, arg) in self.args.iter().enumerate() {
+            for sep in [':', '='] {
+                if let Some(value) = arg.strip_prefix(&format!("{}{}", key, sep)) {
+                    self.processed.insert(i);
+                    return Some(value.to_string());
+                }
+            }
+        }
+        None
+    }
+
+    // Parse array arguments: key=1,2,3 or key:a,b,c
+    pub fn get_array(&mut self, key: &str) -> Option<Vec<String>> {
+        self.get_kv(key)
+            .map(|value| value.split(',').map(|s| s.trim().to_string()).collect())
+    }
+
+    pub fn remaining(&self) -> Vec<String> {
+        self.args
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.processed.contains(i))
+            .map(|(_, arg)| arg.clone())
+            .collect()
+    }
+
+    pub fn all(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn join(&self, sep: &str) -> String {
+        self.remaining().join(sep)
+    }
+
+    pub fn len(&self) -> usize {
+        self.remaining().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Replace $1..$N, $@ and $# with args, then expand context variables
+    pub fn expand(&self, template: &str) -> String {
+        let remaining = self.remaining();
+        let mut result = template.to_string();
+        for (i, arg) in remaining.iter().enumerate().rev() {
+            result = result.replace(&format!("${}", i + 1), arg);
+        }
+        result = result.replace("$@", &remaining.join(" "));
+        result = result.replace("$#", &remaining.len().to_string());
+        expand_vars(&result)
+    }
+}
+
+/// Removes a global option from a full argv (`argv[0]` is the script) so
+/// commands never see it. With `takes_value` the value may follow as the
+/// next word or as `flag=value`. Scanning stops at `--`. Returns the last
+/// value given ("1" for a plain flag), or None when the option is absent.
+pub fn take_global(argv: &mut Vec<String>, flag: &str, takes_value: bool) -> Option<String> {
+    let prefix = format!("{}=", flag);
+    let mut found = None;
+    let mut i = 1;
+    while i < argv.len() && argv[i] != "--" {
+        if argv[i] == flag {
+            argv.remove(i);
+            if !takes_value {
+                found = Some("1".to_string());
+            } else if i < argv.len() {
+                found = Some(argv.remove(i));
+            } else {
+                found = Some(String::new());
+            }
+        } else if let Some(value) = argv[i].strip_prefix(&prefix).filter(|_| takes_value) {
+            found = Some(value.to_string());
+            argv.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    found
+}
+
+/// Maps `--flag`, `--key=value` and `-f` style options onto `opt_*` context
+/// vars, so `--yes` becomes `opt_yes=1` and `--env=prod` becomes `opt_env=prod`.
+pub fn apply_options(args: &Args) {
+    for arg in args.all() {
+        if let Some(long) = arg.strip_prefix("--") {
+            match long.split_once('=') {
+                Some((key, value)) => set_var(format!("opt_{}", key.replace('-', "_")), value),
+                None if !long.is_empty() => set_var(format!("opt_{}", long.replace('-', "_")), "1"),
+                None => {}
+            }
+        } else if let Some(short) = arg.strip_prefix('-') {
+            for c in short.chars().filter(|c| c.is_ascii_alphabetic()) {
+                let name = match c {
+                    'y' => "yes",
+                    'q' => "quiet",
+                    'd' => "debug",
+                    'f' => "force",
+                    _ => "",
+                };
+                if name.is_empty() {
+                    set_var(format!("opt_{}", c), "1");
+                } else {
+                    set_var(format!("opt_{}", name), "1");
+                }
+            }
+        }
+    }
+}
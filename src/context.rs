@@ -0,0 +1,834 @@
+//! Global context: the RSB equivalent of the shell environment.
+//!
+//! Variables, the call stack, the function registry, event handlers and the
+//! color/glyph tables all live here behind process-wide mutexes.
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// How many variable references expand_vars_recursive will follow
+pub const MAX_EXPAND_DEPTH: usize = 16;
+
+// Global context - like shell environment variables
+pub struct Context {
+    vars: HashMap<String, String>,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Context {
+            vars: HashMap::new(),
+        }
+    }
+
+    pub fn set<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) {
+        self.vars.insert(key.into(), value.into());
+    }
+
+    pub fn get(&self, key: &str) -> String {
+        self.vars.get(key).cloned().unwrap_or_default()
+    }
+
+    pub fn has(&self, key: &str) -> bool {
+        self.vars.contains_key(key)
+    }
+
+    pub fn unset(&mut self, key: &str) {
+        self.vars.remove(key);
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.vars.keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Single-pass `$VAR` / `${VAR}` expansion. Unset variables expand to "".
+    ///
+    /// `\$` escapes the dollar: `\$HOME` yields a literal `$HOME`. `$$` is
+    /// passed through untouched; it is the natural alternative escape should
+    /// backslashes prove awkward in some callers.
+    pub fn expand(&self, text: &str) -> String {
+        if !text.contains('$') {
+            return text.to_string();
+        }
+        VAR_PATTERN
+            .replace_all(text, |caps: &Captures| {
+                if caps.get(0).is_some_and(|m| m.as_str() == "\\$") {
+                    return "$".to_string();
+                }
+                let name = caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str());
+                self.get(name)
+            })
+            .to_string()
+    }
+
+    /// Expands repeatedly so `A=$B`, `B=hello` resolves `$A` to `hello`.
+    /// Chains deeper than `MAX_EXPAND_DEPTH` and cycles are left unexpanded.
+    pub fn expand_recursive(&self, text: &str) -> String {
+        self.expand_chain(text, &mut Vec::new())
+    }
+
+    fn expand_chain(&self, text: &str, chain: &mut Vec<String>) -> String {
+        if !text.contains('$') {
+            return text.to_string();
+        }
+        VAR_PATTERN
+            .replace_all(text, |caps: &Captures| {
+                let whole = caps.get(0).map_or("", |m| m.as_str());
+                if whole == "\\$" {
+                    return "$".to_string();
+                }
+                let name = caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str());
+                if chain.iter().any(|n| n == name) || chain.len() >= MAX_EXPAND_DEPTH {
+                    return whole.to_string();
+                }
+                chain.push(name.to_string());
+                let value = self.expand_chain(&self.get(name), chain);
+                chain.pop();
+                value
+            })
+            .to_string()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CallFrame {
+    pub function: String,
+    pub args: Vec<String>,
+    pub timestamp: std::time::SystemTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct EventData {
+    pub event_type: String,
+    pub data: HashMap<String, String>,
+}
+
+pub type EventHandler = Arc<dyn Fn(&EventData) + Send + Sync>;
+pub type VarObserver = Arc<dyn Fn(&str, &str) + Send + Sync>;
+
+lazy_static! {
+    pub static ref CTX: Mutex<Context> = Mutex::new(Context::new());
+    static ref VAR_PATTERN: Regex =
+        Regex::new(r"\\\$|\$\{([A-Za-z_][A-Za-z0-9_]*)\}|\$([A-Za-z_][A-Za-z0-9_]*)").unwrap();
+    static ref CALL_STACK: Mutex<Vec<CallFrame>> = Mutex::new(Vec::new());
+    static ref FUNCTION_REGISTRY: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+    static ref USAGE_REGISTRY: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+    static ref EVENT_HANDLERS: Mutex<HashMap<String, Vec<EventHandler>>> =
+        Mutex::new(HashMap::new());
+    static ref VAR_OBSERVERS: Mutex<HashMap<String, Vec<VarObserver>>> = Mutex::new(HashMap::new());
+    pub static ref COLORS: Mutex<HashMap<String, String>> = Mutex::new(default_colors());
+    pub static ref GLYPHS: Mutex<HashMap<String, String>> = Mutex::new(default_glyphs());
+}
+
+// --- Variables -------------------------------------------------------------
+
+// Lets set_var skip the observer lookup until someone calls on_change
+static HAS_OBSERVERS: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+pub fn set_var<K: Into<String>, V: Into<String>>(key: K, value: V) {
+    if !HAS_OBSERVERS.load(std::sync::atomic::Ordering::Relaxed) {
+        CTX.lock().unwrap().set(key, value);
+        return;
+    }
+    let (key, value) = (key.into(), value.into());
+    let old = {
+        let mut ctx = CTX.lock().unwrap();
+        let old = ctx.get(&key);
+        ctx.set(key.clone(), value.clone());
+        old
+    };
+    notify_change(&key, &old, &value);
+}
+
+pub fn get_var(key: &str) -> String {
+    CTX.lock().unwrap().get(key)
+}
+
+pub fn has_var(key: &str) -> bool {
+    CTX.lock().unwrap().has(key)
+}
+
+pub fn unset_var(key: &str) {
+    let old = {
+        let mut ctx = CTX.lock().unwrap();
+        let old = ctx.get(key);
+        ctx.unset(key);
+        old
+    };
+    if HAS_OBSERVERS.load(std::sync::atomic::Ordering::Relaxed) {
+        notify_change(key, &old, "");
+    }
+}
+
+/// Calls `observer(old, new)` whenever `key` changes value through
+/// `set_var`/`unset_var` (an unset var reads as ""). Runs after the context
+/// lock is released, so observers may set other vars.
+pub fn on_change<F>(key: &str, observer: F)
+where
+    F: Fn(&str, &str) + Send + Sync + 'static,
+{
+    VAR_OBSERVERS
+        .lock()
+        .unwrap()
+        .entry(key.to_string())
+        .or_default()
+        .push(Arc::new(observer));
+    HAS_OBSERVERS.store(true, std::sync::atomic::Ordering::Relaxed);
+}
+
+fn notify_change(key: &str, old: &str, new: &str) {
+    if old == new {
+        return;
+    }
+    let observers = VAR_OBSERVERS.lock().unwrap().get(key).cloned();
+    for observer in observers.unwrap_or_default() {
+        observer(old, new);
+    }
+}
+
+pub fn get_all_vars() -> HashMap<String, String> {
+    CTX.lock().unwrap().vars.clone()
+}
+
+pub fn expand_vars(text: &str) -> String {
+    CTX.lock().unwrap().expand(text)
+}
+
+pub fn expand_vars_recursive(text: &str) -> String {
+    CTX.lock().unwrap().expand_recursive(text)
+}
+
+/// Sets context and environment vars together, restoring both (including
+/// "was unset") when dropped. Used by `env_guard!`.
+pub struct VarGuard {
+    saved: Vec<(String, Option<String>, Option<String>)>,
+}
+
+impl VarGuard {
+    pub fn set(pairs: &[(&str, &str)]) -> Self {
+        let mut saved = Vec::new();
+        for (key, value) in pairs {
+            let context_prior = has_var(key).then(|| get_var(key));
+            saved.push((key.to_string(), context_prior, std::env::var(key).ok()));
+            set_var(*key, *value);
+            std::env::set_var(key, value);
+        }
+        VarGuard { saved }
+    }
+}
+
+impl Drop for VarGuard {
+    fn drop(&mut self) {
+        for (key, context_prior, env_prior) in self.saved.drain(..).rev() {
+            match context_prior {
+                Some(v) => set_var(key.as_str(), v),
+                None => unset_var(&key),
+            }
+            match env_prior {
+                Some(v) => std::env::set_var(&key, v),
+                None => std::env::remove_var(&key),
+            }
+        }
+    }
+}
+
+// --- Typed getters ---------------------------------------------------------
+
+/// Parses a var as an integer, falling back to `default` if unset or invalid.
+pub fn get_var_int(key: &str, default: i64) -> i64 {
+    get_var(key).trim().parse().unwrap_or(default)
+}
+
+/// True for `true`, `1`, `yes` or `on` (any case); false otherwise.
+pub fn get_var_bool(key: &str) -> bool {
+    matches!(
+        get_var(key).trim().to_lowercase().as_str(),
+        "true" | "1" | "yes" | "on"
+    )
+}
+
+/// An indexed array if one exists, else the value split on whitespace.
+pub fn get_var_list(key: &str) -> Vec<String> {
+    crate::utils::get_array(key)
+}
+
+// --- Call stack & function registry ---------------------------------------
+
+pub fn push_call(function: &str, args: &[String]) {
+    CALL_STACK.lock().unwrap().push(CallFrame {
+        function: function.to_string(),
+        args: args.to_vec(),
+        timestamp: std::time::SystemTime::now(),
+    });
+}
+
+pub fn pop_call() -> Option<CallFrame> {
+    CALL_STACK.lock().unwrap().pop()
+}
+
+/// Runs a dispatch handler inside a call frame, emitting `command_start`
+/// (`command`, `args`) before it and `command_end` (`command`, `code`,
+/// `duration_ms`) after, like bash's DEBUG/RETURN traps.
+pub fn call_handler<F>(name: &str, args: crate::args::Args, handler: F) -> i32
+where
+    F: FnOnce(crate::args::Args) -> i32,
+{
+    let mut data = HashMap::new();
+    data.insert("command".to_string(), name.to_string());
+    data.insert("args".to_string(), args.all().join(" "));
+    push_call(name, args.all());
+    emit_event("command_start", &data);
+
+    let started = std::time::Instant::now();
+    let code = handler(args);
+    data.insert("code".to_string(), code.to_string());
+    data.insert("duration_ms".to_string(), started.elapsed().as_millis().to_string());
+    emit_event("command_end", &data);
+    pop_call();
+    code
+}
+
+pub fn get_call_stack() -> Vec<CallFrame> {
+    CALL_STACK.lock().unwrap().clone()
+}
+
+pub fn register_function(name: &str, description: &str) {
+    FUNCTION_REGISTRY
+        .lock()
+        .unwrap()
+        .insert(name.to_string(), description.to_string());
+}
+
+pub fn list_functions() -> Vec<(String, String)> {
+    let mut funcs: Vec<(String, String)> = FUNCTION_REGISTRY
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    funcs.sort();
+    funcs
+}
+
+pub fn is_function(name: &str) -> bool {
+    FUNCTION_REGISTRY.lock().unwrap().contains_key(name)
+}
+
+/// Records the usage line shown by `help <command>`.
+pub fn register_usage(name: &str, usage: &str) {
+    USAGE_REGISTRY
+        .lock()
+        .unwrap()
+        .insert(name.to_string(), usage.to_string());
+}
+
+pub fn get_usage(name: &str) -> Option<String> {
+    USAGE_REGISTRY.lock().unwrap().get(name).cloned()
+}
+
+// Built-in `help` for dispatch!
+pub fn show_help() {
+    crate::echo!("{bold}{blue}$SCRIPT_NAME{reset} - RSB Application");
+    crate::echo!();
+    crate::echo!("Available commands:");
+    for (name, desc) in list_functions() {
+        crate::utils::write_out(&format!("  {:<15} {}\n", name, desc));
+    }
+    crate::echo!();
+    crate::echo!("Built-in commands:");
+    crate::echo!("  {cyan}help{reset}            Show this help");
+    crate::echo!("  {cyan}inspect{reset}         List all available functions");
+    crate::echo!("  {cyan}stack{reset}           Show current call stack");
+}
+
+// `help <command>`: that command's usage, or the general help if unknown
+pub fn show_command_help(name: &str) {
+    match get_usage(name) {
+        Some(usage) => {
+            crate::echo!(format!("{{bold}}Usage:{{reset}} $SCRIPT_NAME {}", usage));
+            if let Some(desc) = FUNCTION_REGISTRY.lock().unwrap().get(name) {
+                crate::utils::write_out(&format!("  {}\n", desc));
+            }
+        }
+        None => show_help(),
+    }
+}
+
+pub fn show_functions() {
+    crate::echo!("{bold}Available functions:{reset}");
+    for (name, desc) in list_functions() {
+        crate::utils::write_out(&format!("  {:<20} {}\n", name, desc));
+    }
+}
+
+pub fn show_call_stack() {
+    let stack = get_call_stack();
+    if stack.is_empty() {
+        crate::echo!("Call stack is empty");
+        return;
+    }
+
+    crate::echo!("{bold}Call stack (most recent first):{reset}");
+    for (i, frame) in stack.iter().rev().enumerate() {
+        let elapsed = frame
+            .timestamp
+            .elapsed()
+            .map(|d| format!("{}ms", d.as_millis()))
+            .unwrap_or_else(|_| "?".to_string());
+        crate::utils::write_out(&format!(
+            "  {}: {} {} ({})\n",
+            i,
+            frame.function,
+            frame.args.join(" "),
+            elapsed
+        ));
+    }
+}
+
+// --- Events (trap!) --------------------------------------------------------
+
+pub fn register_handler(event: &str, handler: EventHandler) {
+    EVENT_HANDLERS
+        .lock()
+        .unwrap()
+        .entry(event.to_string())
+        .or_default()
+        .push(handler);
+}
+
+pub fn emit_event(event: &str, data: &HashMap<String, String>) {
+    // Clone the handlers out so a handler may register/emit without deadlocking.
+    let handlers = EVENT_HANDLERS.lock().unwrap().get(event).cloned();
+    if let Some(handlers) = handlers {
+        let event_data = EventData {
+            event_type: event.to_string(),
+            data: data.clone(),
+        };
+        for handler in handlers {
+            handler(&event_data);
+        }
+    }
+}
+
+pub fn trap_handler<F>(event: &str, handler: F)
+where
+    F: Fn(&EventData) + Send + Sync + 'static,
+{
+    register_handler(event, Arc::new(handler));
+}
+
+/// Emits the `EXIT` event with the process exit code.
+pub fn emit_exit(code: i32) {
+    let mut data = HashMap::new();
+    data.insert("code".to_string(), code.to_string());
+    emit_event("EXIT", &data);
+}
+
+// --- Colors & glyphs -------------------------------------------------------
+
+fn default_colors() -> HashMap<String, String> {
+    [
+        ("red", "\x1b[31m"),
+        ("green", "\x1b[32m"),
+        ("yellow", "\x1b[33m"),
+        ("blue", "\x1b[34m"),
+        ("magenta", "\x1b[35m"),
+        ("cyan", "\x1b[36m"),
+        ("white", "\x1b[37m"),
+        ("grey", "\x1b[90m"),
+        ("bold", "\x1b[1m"),
+        ("dim", "\x1b[2m"),
+        ("underline", "\x1b[4m"),
+        ("reset", "\x1b[0m"),
+    ]
+    .iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect()
+}
+
+fn default_glyphs() -> HashMap<String, String> {
+    [
+        ("pass", "✓"),
+        ("fail", "✗"),
+        ("info", "ℹ"),
+        ("warn", "⚠"),
+        ("debug", "…"),
+        ("trace", "»"),
+        ("fatal", "☠"),
+    ]
+    .iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect()
+}
+
+pub fn get_color(name: &str) -> String {
+    COLORS.lock().unwrap().get(name).cloned().unwrap_or_default()
+}
+
+pub fn get_glyph(name: &str) -> String {
+    GLYPHS.lock().unwrap().get(name).cloned().unwrap_or_default()
+}
+
+/// Applies an `RSB_COLORS` spec: a comma-separated list of `none`,
+/// `name=SGR` color overrides (e.g. `red=1;31`) and `glyph.name=char`.
+pub fn parse_rsb_colors(spec: &str) {
+    for token in spec.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if token == "none" {
+            for code in COLORS.lock().unwrap().values_mut() {
+                code.clear();
+            }
+            continue;
+        }
+        if let Some((name, value)) = token.split_once('=') {
+            if let Some(glyph) = name.strip_prefix("glyph.") {
+                GLYPHS
+                    .lock()
+                    .unwrap()
+                    .insert(glyph.to_string(), value.to_string());
+            } else {
+                COLORS
+                    .lock()
+                    .unwrap()
+                    .insert(name.to_string(), format!("\x1b[{}m", value));
+            }
+        }
+    }
+}
+
+/// Switches to a color/glyph preset: `default`, `high_contrast`,
+/// `colorblind_safe` (no red/green pairing) or `minimal` (no color, ASCII
+/// glyphs). Role colors are drawn from the extended palette's theme
+/// suggestions. Returns false, changing nothing, for an unknown name.
+pub fn set_theme(name: &str) -> bool {
+    use crate::visual::extended_colors::{generate_kb_theme_suggestions, get_extended_color};
+
+    // Which suggestion each role uses, by category, in the palette's order
+    let picks: &[(&str, usize)] = match name {
+        "default" | "minimal" => &[],
+        "high_contrast" => &[("error", 1), ("warning", 2), ("success", 2), ("info", 2), ("debug", 1), ("accent", 1)],
+        "colorblind_safe" => &[("error", 2), ("warning", 1), ("success", 1), ("info", 0), ("debug", 1), ("accent", 0)],
+        _ => return false,
+    };
+    let mut colors = default_colors();
+    let mut glyphs = default_glyphs();
+    let suggestions = generate_kb_theme_suggestions();
+    for (category, index) in picks {
+        let key = match *category {
+            "error" => "red",
+            "warning" => "yellow",
+            "success" => "green",
+            "info" => "blue",
+            "debug" => "grey",
+            _ => "magenta",
+        };
+        let choice = suggestions
+            .iter()
+            .find(|(c, _)| c == category)
+            .and_then(|(_, names)| names.get(*index))
+            .and_then(|n| get_extended_color(n));
+        if let Some(code) = choice {
+            colors.insert(key.to_string(), code.to_string());
+        }
+    }
+    if name == "minimal" {
+        colors.values_mut().for_each(String::clear);
+        for (glyph, ascii) in [("pass", "+"), ("fail", "x"), ("info", "-"), ("warn", "!"), ("debug", "."), ("trace", ">"), ("fatal", "X")] {
+            glyphs.insert(glyph.to_string(), ascii.to_string());
+        }
+    }
+    *COLORS.lock().unwrap() = colors;
+    *GLYPHS.lock().unwrap() = glyphs;
+    true
+}
+
+// --- Bootstrap ---------------------------------------------------------------
+
+// XDG+ setup with RSB namespacing
+pub fn setup_xdg_paths() {
+    set_var("XDG_HOME", expand_vars("$HOME/.local"));
+    set_var("XDG_LIB", expand_vars("$XDG_HOME/lib"));
+    set_var("XDG_ETC", expand_vars("$XDG_HOME/etc"));
+    set_var("XDG_BIN", expand_vars("$XDG_HOME/bin"));
+    set_var("XDG_DATA", expand_vars("$XDG_HOME/data"));
+    set_var("XDG_TMP", expand_vars("$HOME/.cache/tmp"));
+}
+
+pub fn setup_rsb_paths() {
+    set_var("RSB_LIB", expand_vars("$XDG_LIB/rsb"));
+    set_var("RSB_BIN", expand_vars("$XDG_BIN/rsb"));
+    set_var("RSB_ETC", expand_vars("$XDG_ETC/rsb"));
+    set_var("RSB_DATA", expand_vars("$XDG_DATA/rsb"));
+}
+
+/// Whether side effects should be skipped: `--dry-run` (`opt_dry_run`) or a
+/// truthy RSB_DRY_RUN in the context or environment.
+pub fn dry_run() -> bool {
+    let truthy = |v: &str| matches!(v, "1" | "true" | "yes");
+    get_var("opt_dry_run") == "1"
+        || truthy(&get_var("RSB_DRY_RUN"))
+        || std::env::var("RSB_DRY_RUN").is_ok_and(|v| truthy(&v))
+}
+
+/// Under dry-run, logs "would <action>" and returns true so the caller
+/// skips the action; otherwise returns false.
+pub fn dry_run_skip(action: &str) -> bool {
+    if !dry_run() {
+        return false;
+    }
+    crate::info!("would {}", action);
+    true
+}
+
+pub fn setup_standard_modes() {
+    // BashFX-style mode variables from environment
+    if std::env::var("DEBUG").is_ok() {
+        set_var("DEBUG_MODE", "1");
+    }
+    if std::env::var("DEV").is_ok() {
+        set_var("DEV_MODE", "1");
+    }
+    if std::env::var("QUIET").is_ok() {
+        set_var("QUIET_MODE", "1");
+    }
+    if std::env::var("TRACE").is_ok() {
+        set_var("TRACE_MODE", "1");
+    }
+}
+
+pub fn setup_script_awareness(args: &[String]) {
+    let script_path = args.first().map(String::as_str).unwrap_or("rsb");
+    let path = std::path::Path::new(script_path);
+    let script_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("rsb");
+    let script_dir = path.parent().and_then(|p| p.to_str()).unwrap_or(".");
+
+    set_var("SCRIPT_NAME", script_name);
+    set_var("SCRIPT_PATH", script_path);
+    set_var("SCRIPT_DIR", script_dir);
+}
+
+// Standard RSB bootstrap function
+pub fn rsb_bootstrap(args: &[String]) {
+    // Front-load all environment variables
+    for (key, value) in std::env::vars() {
+        set_var(key, value);
+    }
+
+    setup_xdg_paths();
+    setup_rsb_paths();
+    setup_standard_modes();
+    setup_script_awareness(args);
+    crate::visual::width_plugin::refresh_cols();
+
+    if let Ok(spec) = std::env::var("RSB_COLORS") {
+        parse_rsb_colors(&spec);
+    }
+}
+
+// --- Config files ------------------------------------------------------------
+
+pub fn load_config_file(path: &str) {
+    let expanded_path = expand_vars(path);
+    if !std::path::Path::new(&expanded_path).exists() {
+        crate::trace!("Config file not found: {}", expanded_path);
+        return;
+    }
+
+    let content = crate::fs::read_file(&expanded_path);
+    parse_config_content(&content);
+    crate::trace!("Loaded config: {}", expanded_path);
+}
+
+/// Where `load_config_search` looks for `name`, lowest precedence first:
+/// `/etc`, `$XDG_ETC`, `$HOME/.config/$SCRIPT_NAME`, then the current dir.
+/// Locations whose vars are unset are left out.
+pub fn config_search_paths(name: &str) -> Vec<String> {
+    let mut paths = vec![format!("/etc/{}", name)];
+    if has_var("XDG_ETC") {
+        paths.push(format!("{}/{}", get_var("XDG_ETC"), name));
+    }
+    if has_var("HOME") && has_var("SCRIPT_NAME") {
+        paths.push(format!("{}/.config/{}/{}", get_var("HOME"), get_var("SCRIPT_NAME"), name));
+    }
+    paths.push(format!("./{}", name));
+    paths
+}
+
+/// Loads every existing `config_search_paths` file in order, so later ones
+/// override earlier ones. Returns the paths that were loaded.
+pub fn load_config_search(name: &str) -> Vec<String> {
+    let mut loaded = Vec::new();
+    for path in config_search_paths(name) {
+        if std::path::Path::new(&path).is_file() && !loaded.contains(&path) {
+            load_config_file(&path);
+            loaded.push(path);
+        }
+    }
+    if loaded.is_empty() {
+        crate::trace!("No {} found in the config search path", name);
+    }
+    loaded
+}
+
+/// Applies `KEY=value` lines to the context, returning the keys it set.
+pub fn parse_config_content(content: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+
+        // Skip comments and empty lines
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once('=') {
+            let key = key.trim();
+            let value = unquote(value.trim());
+
+            // Handle bash-style arrays: ARRAY=(item1 item2 item3)
+            if value.starts_with('(') && value.ends_with(')') {
+                let items: Vec<&str> = value[1..value.len() - 1].split_whitespace().collect();
+                crate::utils::set_array(key, &items);
+            } else {
+                set_var(key, value);
+            }
+            keys.push(key.to_string());
+        }
+    }
+    keys
+}
+
+/// Loads a dotenv file into both the context and the process environment,
+/// so child commands inherit the values. Accepts `export KEY=value` lines,
+/// quoted values and trailing `# comments` on unquoted values.
+pub fn source_env_file(path: &str) {
+    let expanded_path = expand_vars(path);
+    if !std::path::Path::new(&expanded_path).is_file() {
+        crate::warn!("Env file not found: {}", expanded_path);
+        return;
+    }
+
+    for line in crate::fs::read_file(&expanded_path).lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        let mut value = value.trim();
+        if !value.starts_with(['"', '\'']) {
+            value = value.split(" #").next().unwrap_or("").trim_end();
+        }
+        let value = unquote(value);
+
+        set_var(key, value);
+        std::env::set_var(key, value);
+    }
+    crate::trace!("Sourced env: {}", expanded_path);
+}
+
+fn unquote(value: &str) -> &str {
+    let quoted = value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')));
+    if quoted {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    }
+}
+
+pub fn save_config_file(path: &str, keys: &[&str]) {
+    let mut content = String::from("# RSB Configuration File\n\n");
+
+    for key in keys {
+        if !has_var(key) {
+            continue;
+        }
+        if has_var(&format!("{}_LENGTH", key)) {
+            let items = crate::utils::get_array(key);
+            content.push_str(&format!("{}=({})\n", key, items.join(" ")));
+        } else {
+            let value = get_var(key);
+            if value.contains(' ') {
+                content.push_str(&format!("{}=\"{}\"\n", key, value));
+            } else {
+                content.push_str(&format!("{}={}\n", key, value));
+            }
+        }
+    }
+
+    crate::fs::write_file(path, &content);
+}
+
+// --- Key-value store -------------------------------------------------------------
+//
+// A config-style file whose keys live in the context while open; every
+// change is written straight back.
+
+struct KvStore {
+    path: String,
+    keys: Vec<String>,
+}
+
+lazy_static! {
+    static ref KV_STORE: Mutex<Option<KvStore>> = Mutex::new(None);
+}
+
+pub fn kv_open(path: &str) {
+    let path = expand_vars(path);
+    let keys = parse_config_content(&crate::fs::read_file(&path));
+    *KV_STORE.lock().unwrap() = Some(KvStore { path, keys });
+}
+
+pub fn kv_get(key: &str) -> String {
+    get_var(key)
+}
+
+pub fn kv_set(key: &str, value: &str) {
+    set_var(key, value);
+    kv_update(|keys| {
+        if !keys.iter().any(|k| k == key) {
+            keys.push(key.to_string());
+        }
+    });
+}
+
+pub fn kv_del(key: &str) {
+    unset_var(key);
+    kv_update(|keys| keys.retain(|k| k != key));
+}
+
+fn kv_update<F: FnOnce(&mut Vec<String>)>(change: F) {
+    let mut store = KV_STORE.lock().unwrap();
+    let Some(store) = store.as_mut() else {
+        crate::error!("No kv store open; use kv!(open: path) first");
+        return;
+    };
+    change(&mut store.keys);
+    let keys: Vec<&str> = store.keys.iter().map(String::as_str).collect();
+    save_config_file(&store.path, &keys);
+}
+
+pub fn export_vars(path: &str) {
+    let vars = get_all_vars();
+    let mut keys: Vec<&String> = vars.keys().collect();
+    keys.sort();
+    let content: String = keys
+        .iter()
+        .map(|k| format!("export {}={}\n", k, crate::os::sh_quote(&vars[*k])))
+        .collect();
+    crate::fs::write_file(path, &content);
+}
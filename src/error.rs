@@ -0,0 +1,71 @@
+//! Errors for the non-exiting `try_` variants of os/fs helpers.
+//!
+//! The plain helpers keep their script-style behaviour (report and exit);
+//! the `try_` forms return these instead so RSB can be embedded in a
+//! long-running program.
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum RsbError {
+    /// A command ran but exited non-zero (127 if it couldn't be started).
+    Command {
+        cmd: String,
+        status: i32,
+        stderr: String,
+    },
+    /// A lock file already exists; `holder` is the PID recorded in it.
+    LockHeld { path: String, holder: String },
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    /// An HTTP request got an error status; 0 means no response at all.
+    Http { url: String, status: u16 },
+    /// A signal couldn't be sent (unknown name, no such process, no permission).
+    Signal { pid: i32, reason: String },
+    /// No usable system clipboard (headless session, no display server).
+    Clipboard(String),
+}
+
+impl RsbError {
+    /// Exit status a script should use for this error.
+    pub fn status(&self) -> i32 {
+        match self {
+            RsbError::Command { status, .. } => *status,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for RsbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RsbError::Command { cmd, status, stderr } if stderr.is_empty() => {
+                write!(f, "Command failed ({}): {}", status, cmd)
+            }
+            RsbError::Command { cmd, status, stderr } => {
+                write!(f, "Command failed ({}): {}: {}", status, cmd, stderr)
+            }
+            RsbError::LockHeld { path, holder } => {
+                write!(f, "Lock already held: {} (pid {})", path, holder)
+            }
+            RsbError::Io { path, source } => write!(f, "{}: {}", path, source),
+            RsbError::Http { url, status: 0 } => write!(f, "Connection failed: {}", url),
+            RsbError::Http { url, status } => write!(f, "HTTP {}: {}", status, url),
+            RsbError::Signal { pid, reason } => write!(f, "Cannot signal {}: {}", pid, reason),
+            RsbError::Clipboard(reason) => write!(f, "Clipboard unavailable: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for RsbError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RsbError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+pub type RsbResult<T> = Result<T, RsbError>;
@@ -0,0 +1,888 @@
+//! File system operations that speak strings.
+//!
+//! Paths are expanded against the context before use, mutations report
+//! through the stderr macros, and unrecoverable failures exit like `set -e`.
+
+use crate::context::{expand_vars, get_var};
+use crate::error::{RsbError, RsbResult};
+use crate::streams::Stream;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+lazy_static! {
+    static ref TEMP_FILES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+}
+
+// --- Tests ([[ -f ]] and friends) ---------------------------------------------
+
+pub fn is_file(path: &str) -> bool {
+    Path::new(&expand_vars(path)).is_file()
+}
+
+pub fn is_dir(path: &str) -> bool {
+    Path::new(&expand_vars(path)).is_dir()
+}
+
+pub fn is_entity(path: &str) -> bool {
+    Path::new(&expand_vars(path)).exists()
+}
+
+pub fn is_link(path: &str) -> bool {
+    Path::new(&expand_vars(path)).is_symlink()
+}
+
+pub fn is_nonempty_file(path: &str) -> bool {
+    std::fs::metadata(expand_vars(path))
+        .map(|m| m.is_file() && m.len() > 0)
+        .unwrap_or(false)
+}
+
+#[cfg(unix)]
+fn has_mode_bits(path: &str, mask: u32) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(expand_vars(path))
+        .map(|m| m.permissions().mode() & mask != 0)
+        .unwrap_or(false)
+}
+
+pub fn is_readable(path: &str) -> bool {
+    std::fs::File::open(expand_vars(path)).is_ok()
+}
+
+pub fn is_writable(path: &str) -> bool {
+    std::fs::metadata(expand_vars(path))
+        .map(|m| !m.permissions().readonly())
+        .unwrap_or(false)
+}
+
+pub fn is_executable(path: &str) -> bool {
+    #[cfg(unix)]
+    {
+        has_mode_bits(path, 0o111)
+    }
+    #[cfg(not(unix))]
+    {
+        is_file(path)
+    }
+}
+
+// --- Reading & writing -----------------------------------------------------------
+
+// File operations that return strings
+pub fn read_file(path: &str) -> String {
+    std::fs::read_to_string(expand_vars(path)).unwrap_or_default()
+}
+
+/// Writes (truncating) `content` to `path`, creating missing parent
+/// directories like `mkdir -p`.
+pub fn write_file(path: &str, content: &str) {
+    if crate::context::dry_run_skip(&format!("write: {}", expand_vars(path))) {
+        return;
+    }
+    let opts = WriteOpts {
+        create_dirs: true,
+        ..Default::default()
+    };
+    exit_on_write_error(try_write_file_opts(path, content, &opts));
+}
+
+pub fn append_file(path: &str, content: &str) {
+    if crate::context::dry_run_skip(&format!("append: {}", expand_vars(path))) {
+        return;
+    }
+    let opts = WriteOpts {
+        append: true,
+        ..Default::default()
+    };
+    exit_on_write_error(try_write_file_opts(path, content, &opts));
+}
+
+/// How `write_file_opts` opens its target.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WriteOpts {
+    /// Add to the end instead of truncating.
+    pub append: bool,
+    /// Create missing parent directories first.
+    pub create_dirs: bool,
+    /// Permission bits for a newly created file, e.g. `Some(0o600)` (unix;
+    /// the umask still applies). Existing files keep their mode.
+    pub mode: Option<u32>,
+}
+
+pub fn write_file_opts(path: &str, content: &str, opts: &WriteOpts) {
+    exit_on_write_error(try_write_file_opts(path, content, opts));
+}
+
+pub fn try_write_file_opts(path: &str, content: &str, opts: &WriteOpts) -> RsbResult<()> {
+    let path = expand_vars(path);
+    let io_err = |source| RsbError::Io {
+        path: path.clone(),
+        source,
+    };
+    if opts.create_dirs {
+        if let Some(parent) = Path::new(&path).parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(io_err)?;
+        }
+    }
+    let mut open = std::fs::OpenOptions::new();
+    open.create(true);
+    if opts.append {
+        open.append(true);
+    } else {
+        open.write(true).truncate(true);
+    }
+    #[cfg(unix)]
+    if let Some(mode) = opts.mode {
+        use std::os::unix::fs::OpenOptionsExt;
+        open.mode(mode);
+    }
+    open.open(&path)
+        .and_then(|mut f| f.write_all(content.as_bytes()))
+        .map_err(io_err)
+}
+
+pub(crate) fn exit_on_write_error(result: RsbResult<()>) {
+    if let Err(e) = result {
+        crate::error!("Failed to write {}", e);
+        std::process::exit(1);
+    }
+}
+
+// --- Directory & entity operations -----------------------------------------------
+
+pub fn mkdir_p(path: &str) {
+    let path = expand_vars(path);
+    if let Err(e) = std::fs::create_dir_all(&path) {
+        crate::error!("Failed to create directory {}: {}", path, e);
+        std::process::exit(1);
+    }
+}
+
+pub fn rm(path: &str) {
+    let path = expand_vars(path);
+    if crate::context::dry_run_skip(&format!("rm: {}", path)) {
+        return;
+    }
+    if Path::new(&path).is_file() || Path::new(&path).is_symlink() {
+        std::fs::remove_file(&path).ok();
+    }
+}
+
+pub fn rm_rf(path: &str) {
+    let path = expand_vars(path);
+    if crate::context::dry_run_skip(&format!("rm -rf: {}", path)) {
+        return;
+    }
+    let p = Path::new(&path);
+    if p.is_dir() && !p.is_symlink() {
+        std::fs::remove_dir_all(p).ok();
+    } else if p.exists() || p.is_symlink() {
+        std::fs::remove_file(p).ok();
+    }
+}
+
+pub fn cp(src: &str, dest: &str) {
+    let (src, dest) = (expand_vars(src), expand_vars(dest));
+    if crate::context::dry_run_skip(&format!("cp: {} {}", src, dest)) {
+        return;
+    }
+    if let Err(e) = std::fs::copy(&src, &dest) {
+        crate::error!("Failed to copy {} to {}: {}", src, dest, e);
+        std::process::exit(1);
+    }
+}
+
+pub fn cp_r(src: &str, dest: &str) {
+    let (src, dest) = (expand_vars(src), expand_vars(dest));
+    if crate::context::dry_run_skip(&format!("cp -r: {} {}", src, dest)) {
+        return;
+    }
+    if let Err(e) = copy_recursive(Path::new(&src), Path::new(&dest)) {
+        crate::error!("Failed to copy {} to {}: {}", src, dest, e);
+        std::process::exit(1);
+    }
+}
+
+fn copy_recursive(src: &Path, dest: &Path) -> std::io::Result<()> {
+    if src.is_dir() {
+        std::fs::create_dir_all(dest)?;
+        for entry in std::fs::read_dir(src)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        std::fs::copy(src, dest).map(|_| ())
+    }
+}
+
+pub fn mv(src: &str, dest: &str) {
+    let (src, dest) = (expand_vars(src), expand_vars(dest));
+    if crate::context::dry_run_skip(&format!("mv: {} {}", src, dest)) {
+        return;
+    }
+    if let Err(e) = std::fs::rename(&src, &dest) {
+        crate::error!("Failed to move {} to {}: {}", src, dest, e);
+        std::process::exit(1);
+    }
+}
+
+pub fn touch(path: &str) {
+    let path = expand_vars(path);
+    let result = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|f| f.set_modified(std::time::SystemTime::now()));
+    if let Err(e) = result {
+        crate::error!("Failed to touch {}: {}", path, e);
+        std::process::exit(1);
+    }
+}
+
+/// Sets permissions from an octal ("755") or symbolic ("u+x,go-w") mode.
+/// No-op off Unix.
+pub fn chmod(path: &str, mode: &str) {
+    chmod_path(Path::new(&expand_vars(path)), mode);
+}
+
+/// `chmod -R`: applies `mode` to `path` and everything beneath it. Symbolic
+/// modes resolve against each entry's own bits; symlinks are left alone.
+pub fn chmod_recursive(path: &str, mode: &str) {
+    for entry in walk_tree(Path::new(&expand_vars(path))) {
+        chmod_path(&entry, mode);
+    }
+}
+
+fn chmod_path(path: &Path, mode: &str) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let current = match std::fs::metadata(path) {
+            Ok(m) => m.permissions().mode() & 0o7777,
+            Err(e) => {
+                crate::error!("Failed to chmod {}: {}", path.display(), e);
+                return;
+            }
+        };
+        let bits = match parse_mode(mode, current) {
+            Ok(bits) => bits,
+            Err(e) => {
+                crate::error!("Invalid mode '{}' for {}: {}", mode, path.display(), e);
+                return;
+            }
+        };
+        if let Err(e) = std::fs::set_permissions(path, std::fs::Permissions::from_mode(bits)) {
+            crate::error!("Failed to chmod {}: {}", path.display(), e);
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (path, mode);
+    }
+}
+
+/// Changes owner and/or group. Each may be a name or a numeric id; "" keeps
+/// the current one. Unknown names are reported and nothing changes.
+#[cfg(unix)]
+pub fn chown(path: &str, user: &str, group: &str) {
+    let path = expand_vars(path);
+    if let Some((uid, gid)) = resolve_owner(user, group) {
+        chown_path(Path::new(&path), uid, gid);
+    }
+}
+
+/// `chown -R`: like `chown` for `path` and everything beneath it.
+#[cfg(unix)]
+pub fn chown_recursive(path: &str, user: &str, group: &str) {
+    let path = expand_vars(path);
+    if let Some((uid, gid)) = resolve_owner(user, group) {
+        for entry in walk_tree(Path::new(&path)) {
+            chown_path(&entry, uid, gid);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn chown_path(path: &Path, uid: Option<u32>, gid: Option<u32>) {
+    if let Err(e) = std::os::unix::fs::chown(path, uid, gid) {
+        crate::error!("Failed to chown {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(unix)]
+fn resolve_owner(user: &str, group: &str) -> Option<(Option<u32>, Option<u32>)> {
+    let uid = match user {
+        "" => None,
+        _ => match user.parse().ok().or_else(|| lookup_id(user, false)) {
+            Some(uid) => Some(uid),
+            None => {
+                crate::error!("Unknown user: {}", user);
+                return None;
+            }
+        },
+    };
+    let gid = match group {
+        "" => None,
+        _ => match group.parse().ok().or_else(|| lookup_id(group, true)) {
+            Some(gid) => Some(gid),
+            None => {
+                crate::error!("Unknown group: {}", group);
+                return None;
+            }
+        },
+    };
+    Some((uid, gid))
+}
+
+// uid/gid for a user or group name from the passwd/group databases
+#[cfg(unix)]
+fn lookup_id(name: &str, group: bool) -> Option<u32> {
+    let name = std::ffi::CString::new(name).ok()?;
+    let mut buf = vec![0 as libc::c_char; 16 * 1024];
+    unsafe {
+        if group {
+            let mut grp: libc::group = std::mem::zeroed();
+            let mut found = std::ptr::null_mut();
+            libc::getgrnam_r(name.as_ptr(), &mut grp, buf.as_mut_ptr(), buf.len(), &mut found);
+            (!found.is_null()).then_some(grp.gr_gid)
+        } else {
+            let mut pwd: libc::passwd = std::mem::zeroed();
+            let mut found = std::ptr::null_mut();
+            libc::getpwnam_r(name.as_ptr(), &mut pwd, buf.as_mut_ptr(), buf.len(), &mut found);
+            (!found.is_null()).then_some(pwd.pw_uid)
+        }
+    }
+}
+
+// `path` followed by everything beneath it, without following symlinks
+fn walk_tree(path: &Path) -> Vec<std::path::PathBuf> {
+    let mut paths = vec![path.to_path_buf()];
+    let is_dir = std::fs::symlink_metadata(path).is_ok_and(|m| m.is_dir());
+    if let (true, Ok(entries)) = (is_dir, std::fs::read_dir(path)) {
+        for entry in entries.filter_map(Result::ok) {
+            if entry.file_type().is_ok_and(|t| !t.is_symlink()) {
+                paths.extend(walk_tree(&entry.path()));
+            }
+        }
+    }
+    paths
+}
+
+// Resolves an octal or symbolic mode against the file's current bits
+#[cfg(unix)]
+fn parse_mode(mode: &str, current: u32) -> Result<u32, String> {
+    if !mode.is_empty() && mode.chars().all(|c| c.is_digit(8)) {
+        return u32::from_str_radix(mode, 8).map_err(|e| e.to_string());
+    }
+
+    let mut bits = current;
+    for clause in mode.split(',') {
+        let op_at = clause
+            .find(['+', '-', '='])
+            .ok_or_else(|| format!("missing operator in '{}'", clause))?;
+        let (who, rest) = clause.split_at(op_at);
+        let op = rest.chars().next().unwrap_or('+');
+        let perms = &rest[1..];
+
+        let mut who_mask = 0;
+        for c in who.chars() {
+            who_mask |= match c {
+                'u' => 0o4700,
+                'g' => 0o2070,
+                'o' => 0o1007,
+                'a' => 0o7777,
+                _ => return Err(format!("unknown class '{}'", c)),
+            };
+        }
+        if who_mask == 0 {
+            who_mask = 0o7777;
+        }
+
+        let mut perm_bits = 0;
+        for c in perms.chars() {
+            perm_bits |= match c {
+                'r' => 0o444,
+                'w' => 0o222,
+                'x' => 0o111,
+                // X: execute only if someone can already execute it
+                'X' if bits & 0o111 != 0 => 0o111,
+                'X' => 0,
+                's' => 0o6000,
+                't' => 0o1000,
+                _ => return Err(format!("unknown permission '{}'", c)),
+            };
+        }
+        let delta = perm_bits & who_mask;
+
+        bits = match op {
+            '+' => bits | delta,
+            '-' => bits & !delta,
+            _ => (bits & !who_mask) | delta,
+        };
+    }
+    Ok(bits)
+}
+
+/// Copies `path` to `path + suffix`, returning the backup path.
+pub fn backup_file(path: &str, suffix: &str) -> String {
+    let path = expand_vars(path);
+    let backup = format!("{}{}", path, suffix);
+    cp(&path, &backup);
+    backup
+}
+
+/// Rotating backups: `path.1` is the newest copy; older copies shift up and
+/// anything beyond `keep` is pruned. Returns the new `path.1`.
+pub fn backup_rotated(path: &str, keep: usize) -> String {
+    let path = expand_vars(path);
+    let keep = keep.max(1);
+    let numbered = |n: usize| format!("{}.{}", path, n);
+
+    // Drop anything at or beyond the cap, then shift the rest up by one
+    let mut n = keep;
+    while Path::new(&numbered(n)).exists() {
+        rm(&numbered(n));
+        n += 1;
+    }
+    for n in (1..keep).rev() {
+        if Path::new(&numbered(n)).exists() {
+            mv(&numbered(n), &numbered(n + 1));
+        }
+    }
+
+    let newest = numbered(1);
+    cp(&path, &newest);
+    newest
+}
+
+/// Copies `path` to `path.YYYYmmdd-HHMMSS`, returning the backup path.
+pub fn backup_timestamped(path: &str) -> String {
+    let suffix = format!(".{}", crate::time::date_format("%Y%m%d-%H%M%S"));
+    backup_file(path, &suffix)
+}
+
+/// Expands a shell glob into matching paths, sorted. Patterns without glob
+/// characters are returned as-is; a glob matching nothing warns and yields
+/// nothing.
+pub fn expand_glob(pattern: &str) -> Vec<String> {
+    let pattern = expand_vars(pattern);
+    if !pattern.contains(['*', '?', '[']) {
+        return vec![pattern];
+    }
+    let mut paths: Vec<String> = match glob::glob(&pattern) {
+        Ok(matches) => matches
+            .filter_map(Result::ok)
+            .map(|p| p.to_string_lossy().to_string())
+            .collect(),
+        Err(e) => {
+            crate::warn!("Invalid glob '{}': {}", pattern, e);
+            return Vec::new();
+        }
+    };
+    if paths.is_empty() {
+        crate::warn!("No files match {}", pattern);
+    }
+    paths.sort();
+    paths
+}
+
+/// Runs `action` each time a path matching `pattern` changes, until SIGINT
+/// or until it has run `max_runs` times. Bursts of events (an editor's
+/// write-to-temp-then-rename save) settle for `debounce_ms` and count as a
+/// single change. The directory is watched rather than the files, so
+/// replaced files keep being tracked. Returns the number of runs.
+#[cfg(feature = "watch")]
+pub fn watch<F: FnMut()>(
+    pattern: &str,
+    debounce_ms: u64,
+    max_runs: Option<usize>,
+    mut action: F,
+) -> crate::error::RsbResult<usize> {
+    use notify::{RecursiveMode, Watcher};
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::time::Duration;
+
+    let pattern = expand_vars(pattern);
+    let absolute = if Path::new(&pattern).is_absolute() {
+        std::path::PathBuf::from(&pattern)
+    } else {
+        std::env::current_dir().unwrap_or_default().join(&pattern)
+    };
+    // Watch from the last directory before the first wildcard
+    let base: std::path::PathBuf = absolute
+        .components()
+        .take_while(|c| !c.as_os_str().to_string_lossy().contains(['*', '?', '[']))
+        .collect();
+    let base = if base == absolute { base.parent().map(Path::to_path_buf).unwrap_or(base) } else { base };
+    let watch_err = |e: notify::Error| crate::error::RsbError::Io {
+        path: base.to_string_lossy().to_string(),
+        source: std::io::Error::other(e.to_string()),
+    };
+    let matcher = glob::Pattern::new(&absolute.to_string_lossy()).map_err(|e| crate::error::RsbError::Io {
+        path: pattern.clone(),
+        source: std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()),
+    })?;
+    let options = glob::MatchOptions { require_literal_separator: true, ..Default::default() };
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(watch_err)?;
+    watcher.watch(&base, RecursiveMode::Recursive).map_err(watch_err)?;
+
+    let guard = crate::time::InterruptGuard::install();
+    let poll = Duration::from_millis(100);
+    let quiet = Duration::from_millis(debounce_ms);
+    let mut runs = 0;
+    let relevant = |event: &notify::Result<notify::Event>| {
+        event.as_ref().is_ok_and(|e| !e.kind.is_access() && e.paths.iter().any(|p| matcher.matches_path_with(p, options)))
+    };
+    while !guard.interrupted() && max_runs.is_none_or(|max| runs < max) {
+        match rx.recv_timeout(poll) {
+            Ok(event) if relevant(&event) => {
+                // Debounce: wait until nothing relevant arrives for `quiet`
+                while rx.recv_timeout(quiet).is_ok() {}
+                runs += 1;
+                action();
+            }
+            Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    Ok(runs)
+}
+
+/// Like `grep -rn --include=GLOB`: every line under `root` matching the
+/// regex `pattern`, as (path, 1-based line number, line), in path order.
+/// `glob` filters file names (empty for all files). Unreadable and
+/// non-UTF-8 (binary) files are skipped; symlinked directories aren't followed.
+pub fn grep_recursive(root: &str, pattern: &str, glob: &str) -> Vec<(String, usize, String)> {
+    let re = match regex::Regex::new(pattern) {
+        Ok(re) => re,
+        Err(e) => {
+            crate::warn!("Invalid pattern '{}': {}", pattern, e);
+            return Vec::new();
+        }
+    };
+    let name_filter = if glob.is_empty() { None } else { glob::Pattern::new(glob).ok() };
+    let mut files = Vec::new();
+    collect_files(Path::new(&expand_vars(root)), &mut files);
+    files.sort();
+
+    let mut hits = Vec::new();
+    for file in files {
+        let name_ok = name_filter
+            .as_ref()
+            .is_none_or(|p| file.file_name().is_some_and(|n| p.matches(&n.to_string_lossy())));
+        if !name_ok {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        let path = file.to_string_lossy().to_string();
+        for (i, line) in content.lines().enumerate() {
+            if re.is_match(line) {
+                hits.push((path.clone(), i + 1, line.to_string()));
+            }
+        }
+    }
+    hits
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(Result::ok) {
+        match entry.file_type() {
+            Ok(t) if t.is_dir() => collect_files(&entry.path(), files),
+            Ok(t) if t.is_file() => files.push(entry.path()),
+            _ => {}
+        }
+    }
+}
+
+pub fn list_dir(path: &str) -> Vec<String> {
+    let mut entries: Vec<String> = std::fs::read_dir(expand_vars(path))
+        .map(|rd| {
+            rd.filter_map(Result::ok)
+                .map(|e| e.path().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort();
+    entries
+}
+
+// Splits a path into <into>_parent, <into>_file_name, <into>_file_stem, <into>_extension
+pub fn path_split(path: &str, into: &str) {
+    let path = expand_vars(path);
+    let p = Path::new(&path);
+    let part = |o: Option<&std::ffi::OsStr>| o.map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    crate::context::set_var(
+        format!("{}_parent", into),
+        p.parent().map(|s| s.to_string_lossy().to_string()).unwrap_or_default(),
+    );
+    crate::context::set_var(format!("{}_file_name", into), part(p.file_name()));
+    crate::context::set_var(format!("{}_file_stem", into), part(p.file_stem()));
+    crate::context::set_var(format!("{}_extension", into), part(p.extension()));
+}
+
+// --- Metadata headers ------------------------------------------------------------
+
+/// Reads `# @key: value` header lines from a file into a map.
+pub fn extract_meta_from_file(path: &str) -> HashMap<String, String> {
+    let mut meta = HashMap::new();
+    for line in read_file(path).lines() {
+        if let Some(rest) = line.trim().strip_prefix("# @") {
+            if let Some((key, value)) = rest.split_once(':') {
+                meta.insert(key.trim().to_string(), value.trim().to_string());
+            }
+        }
+    }
+    meta
+}
+
+/// Updates a `# @key: value` header in place, or inserts it at the top
+/// (after a shebang and any existing headers). The rest of the file is kept.
+pub fn set_meta(path: &str, key: &str, value: &str) {
+    let content = read_file(path);
+    let header = format!("# @{}: {}", key, value);
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+    let existing = lines.iter().position(|line| {
+        line.trim()
+            .strip_prefix("# @")
+            .and_then(|rest| rest.split_once(':'))
+            .is_some_and(|(k, _)| k.trim() == key)
+    });
+    match existing {
+        Some(i) => lines[i] = header,
+        None => {
+            let mut at = usize::from(lines.first().is_some_and(|l| l.starts_with("#!")));
+            while lines.get(at).is_some_and(|l| l.trim().starts_with("# @")) {
+                at += 1;
+            }
+            lines.insert(at, header);
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    if content.is_empty() || content.ends_with('\n') {
+        updated.push('\n');
+    }
+    write_file(path, &updated);
+}
+
+// --- sed-style helpers ---------------------------------------------------------
+
+// Lines start..=end, 1-indexed
+pub fn sed_lines(content: &str, start: usize, end: usize) -> String {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(i, _)| i + 1 >= start && *i < end)
+        .map(|(_, l)| l)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Lines matching `pattern` plus `context` lines either side
+pub fn sed_around(content: &str, pattern: &str, context: usize) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut keep = vec![false; lines.len()];
+    for (i, line) in lines.iter().enumerate() {
+        if line.contains(pattern) {
+            let lo = i.saturating_sub(context);
+            let hi = (i + context).min(lines.len() - 1);
+            keep[lo..=hi].iter_mut().for_each(|k| *k = true);
+        }
+    }
+    lines
+        .iter()
+        .zip(keep)
+        .filter(|(_, k)| *k)
+        .map(|(l, _)| *l)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Replace a unique sentinel with content; errors if the sentinel isn't unique
+pub fn sed_insert(content: &str, sentinel: &str, source: &str) -> Result<String, String> {
+    match source.matches(sentinel).count() {
+        1 => Ok(source.replace(sentinel, content)),
+        0 => Err(format!("Sentinel '{}' not found", sentinel)),
+        n => Err(format!("Sentinel '{}' found {} times; must be unique", sentinel, n)),
+    }
+}
+
+// Replace every occurrence of a sentinel with content
+pub fn sed_template(content: &str, sentinel: &str, source: &str) -> String {
+    source.replace(sentinel, content)
+}
+
+pub fn sed_replace(source: &str, from: &str, to: &str) -> String {
+    source.replace(from, to)
+}
+
+pub fn sed_lines_file(path: &str, start: usize, end: usize) -> String {
+    sed_lines(&read_file(path), start, end)
+}
+
+pub fn sed_around_file(path: &str, pattern: &str, context: usize) -> String {
+    sed_around(&read_file(path), pattern, context)
+}
+
+pub fn sed_template_file(path: &str, content: &str, sentinel: &str) {
+    let source = read_file(path);
+    write_file(path, &sed_template(content, sentinel, &source));
+}
+
+// --- Diffs -------------------------------------------------------------------------
+
+/// Unified line diff of two texts; empty when they are identical.
+pub fn diff_text(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+    similar::TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(old_name, new_name)
+        .to_string()
+}
+
+pub fn diff_files(a: &str, b: &str) -> String {
+    let (a, b) = (expand_vars(a), expand_vars(b));
+    diff_text(&read_file(&a), &read_file(&b), &a, &b)
+}
+
+// --- Templates -----------------------------------------------------------------
+
+/// Reads a template and expands `$VAR`/`${VAR}` against the context.
+pub fn render_template(from: &str) -> String {
+    let path = expand_vars(from);
+    if !Path::new(&path).is_file() {
+        crate::error!("Template not found: {}", path);
+        std::process::exit(1);
+    }
+    expand_vars(&read_file(&path))
+}
+
+pub fn template_file(from: &str, to: &str) {
+    let rendered = render_template(from);
+    write_file(to, &rendered);
+}
+
+// --- Temp files & process substitution -------------------------------------------------
+
+/// Returns a fresh temp file path registered for `cleanup_temp_files`.
+pub fn create_temp_file_path(kind: &str) -> String {
+    let tmp_root = if get_var("XDG_TMP").is_empty() {
+        std::env::temp_dir().to_string_lossy().to_string()
+    } else {
+        get_var("XDG_TMP")
+    };
+    std::fs::create_dir_all(&tmp_root).ok();
+    let name = match kind {
+        "pid" => format!("rsb-{}", std::process::id()),
+        "random" => format!("rsb-{}", crate::random::get_rand_alnum(12)),
+        _ => format!(
+            "rsb-{}-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0),
+            crate::random::get_rand_alnum(4)
+        ),
+    };
+    let path = format!("{}/{}", tmp_root.trim_end_matches('/'), name);
+    TEMP_FILES.lock().unwrap().push(path.clone());
+    path
+}
+
+pub fn capture_stream_to_temp_file(stream: Stream) -> String {
+    crate::os::temp_with_content(&stream.to_string())
+}
+
+pub fn cleanup_temp_files() {
+    // Bookkeeping, not a user action, so dry-run doesn't apply
+    for path in TEMP_FILES.lock().unwrap().drain(..) {
+        let p = Path::new(&path);
+        if p.is_dir() && !p.is_symlink() {
+            std::fs::remove_dir_all(p).ok();
+        } else {
+            std::fs::remove_file(p).ok();
+        }
+    }
+}
+
+// --- Binary files as base64 -------------------------------------------------------
+
+/// Base64 of a file's raw bytes, wrapped at 76 columns like `base64`, so
+/// binary content can travel through line-based streams. "" if unreadable.
+pub fn encode_file_base64(path: &str) -> String {
+    use base64::Engine;
+    let path = expand_vars(path);
+    match std::fs::read(&path) {
+        Ok(bytes) => {
+            let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+            encoded
+                .as_bytes()
+                .chunks(76)
+                .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        Err(e) => {
+            crate::error!("Failed to read {}: {}", path, e);
+            String::new()
+        }
+    }
+}
+
+/// Decodes base64 text (whitespace and line breaks ignored) and writes the
+/// bytes to `path`, creating parent directories.
+pub fn decode_base64_to_file(encoded: &str, path: &str) -> RsbResult<()> {
+    use base64::Engine;
+    let path = expand_vars(path);
+    let io_err = |source| RsbError::Io {
+        path: path.clone(),
+        source,
+    };
+    let compact: String = encoded.chars().filter(|c| !c.is_whitespace()).collect();
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(compact)
+        .map_err(|e| io_err(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+    if let Some(parent) = Path::new(&path).parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    std::fs::write(&path, bytes).map_err(io_err)
+}
+
+// --- Hashing ----------------------------------------------------------------------
+
+/// Hashes a string with the system `sha256sum`/`md5sum` (or `shasum`/`md5`).
+pub fn hash_string(content: &str, algo: &str) -> String {
+    let candidates: &[&str] = match algo {
+        "md5" => &["md5sum", "md5 -q"],
+        _ => &["sha256sum", "shasum -a 256"],
+    };
+    for cmd in candidates {
+        let bin = cmd.split_whitespace().next().unwrap_or(cmd);
+        if crate::os::is_command(bin) {
+            let result = crate::os::run_cmd_with_input(cmd, content);
+            if result.status == 0 {
+                return result.output.split_whitespace().next().unwrap_or("").to_string();
+            }
+        }
+    }
+    String::new()
+}
+
+pub fn hash_file(path: &str, algo: &str) -> String {
+    hash_string(&read_file(path), algo)
+}
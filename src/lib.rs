@@ -0,0 +1,54 @@
+//! # RSB - Rebel String-Biased framework
+//!
+//! Bash-like ergonomics for Rust command-line tools: a global variable
+//! context, chainable line streams, string-first file and OS helpers, and a
+//! macro vocabulary that reads like a shell script.
+//!
+//! ```ignore
+//! use rsb::prelude::*;
+//!
+//! fn main() {
+//!     let args = bootstrap!();
+//!     dispatch!(&args, { "build" => do_build });
+//! }
+//! ```
+
+pub mod args;
+pub mod context;
+pub mod error;
+pub mod fs;
+pub mod macros;
+pub mod os;
+pub mod prelude;
+pub mod random;
+pub mod streamable;
+pub mod streams;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod time;
+pub mod utils;
+pub mod visual;
+
+/// Third-party crates re-exported so downstream tools don't need to depend
+/// on them directly.
+pub mod deps {
+    #[cfg(feature = "clipboard")]
+    pub use arboard;
+    pub use base64;
+    pub use chrono;
+    pub use csv;
+    pub use glob;
+    pub use lazy_static;
+    pub use libc;
+    #[cfg(feature = "watch")]
+    pub use notify;
+    pub use rand;
+    #[cfg(feature = "parallel")]
+    pub use rayon;
+    pub use regex;
+    pub use serde_json;
+    pub use similar;
+    pub use sysinfo;
+    pub use urlencoding;
+    pub use uuid;
+}
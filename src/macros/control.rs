@@ -0,0 +1,116 @@
+// Loops, retries, sleeping, timing and dates.
+
+// for i in "${ARR[@]}"
+#[macro_export]
+macro_rules! for_in {
+    ($var:ident in $array:expr => $body:block) => {
+        for $var in $crate::utils::get_array(&$array) $body
+    };
+}
+
+// for_in! on a bounded thread pool; returns the block's values in array order.
+// Without `threads:` the pool has one worker per core.
+#[macro_export]
+macro_rules! pforeach {
+    ($var:ident in $array:expr, threads: $n:expr => $body:block) => {
+        $crate::os::worker_pool($crate::utils::get_array(&$array), $n, |$var: String| $body)
+    };
+    ($var:ident in $array:expr => $body:block) => {
+        $crate::os::worker_pool($crate::utils::get_array(&$array), $crate::os::cpu_count(), |$var: String| $body)
+    };
+}
+
+// Re-run a block (evaluating to bool) until it succeeds; delay is in ms
+#[macro_export]
+macro_rules! retry {
+    ($attempts:expr, delay: $delay_ms:expr => $body:block) => {{
+        let mut __ok = false;
+        for __attempt in 1..=$attempts {
+            if $body {
+                __ok = true;
+                break;
+            }
+            if __attempt < $attempts {
+                $crate::warn!("Attempt {}/{} failed, retrying...", __attempt, $attempts);
+                $crate::time::sleep_ms($delay_ms);
+            }
+        }
+        __ok
+    }};
+    ($attempts:expr => $body:block) => {
+        $crate::retry!($attempts, delay: 1000 => $body)
+    };
+}
+
+#[macro_export]
+macro_rules! sleep {
+    (ms: $ms:expr) => { $crate::time::sleep_ms($ms) };
+    ($secs:expr) => { $crate::time::sleep_secs($secs) };
+}
+
+// Run a block repeatedly for `secs` seconds, sleeping `every` ms (default
+// 1000) between runs. Stops early on SIGINT; returns the iteration count.
+#[macro_export]
+macro_rules! until {
+    ($secs:expr, every: $ms:expr => $body:block) => {{
+        let __deadline = $crate::time::current_epoch_ms() + ($secs as u128) * 1000;
+        let __guard = $crate::time::InterruptGuard::install();
+        let mut __iterations: usize = 0;
+        while $crate::time::current_epoch_ms() < __deadline && !__guard.interrupted() {
+            __iterations += 1;
+            let _ = $body;
+            let __next = $crate::time::current_epoch_ms() + ($ms as u128);
+            $crate::time::sleep_until(__next.min(__deadline));
+        }
+        __iterations
+    }};
+    ($secs:expr => $body:block) => {
+        $crate::until!($secs, every: 1000 => $body)
+    };
+}
+
+// Run a block on a worker thread and wait up to `secs` seconds for its
+// value: Some(value), or None on timeout. Like wait_on_job, but the thread
+// can't be killed in safe Rust, so on timeout it keeps running detached and
+// its result is dropped. The block is a `move` closure body.
+#[macro_export]
+macro_rules! with_timeout {
+    ($secs:expr => $body:block) => {{
+        let (__tx, __rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            __tx.send($body).ok();
+        });
+        __rx.recv_timeout(std::time::Duration::from_secs_f64($secs as f64)).ok()
+    }};
+}
+
+// Time a block, log it at info level and return the Duration
+#[macro_export]
+macro_rules! benchmark {
+    ($body:block) => {{
+        let __start = std::time::Instant::now();
+        let _ = $body;
+        let __elapsed = __start.elapsed();
+        $crate::info!("Benchmark: {:?}", __elapsed);
+        __elapsed
+    }};
+}
+
+// Time a block without logging; returns (value, Duration)
+#[macro_export]
+macro_rules! time_it {
+    ($body:block) => {{
+        let __start = std::time::Instant::now();
+        let __value = $body;
+        (__value, __start.elapsed())
+    }};
+}
+
+#[macro_export]
+macro_rules! date {
+    (epoch) => { $crate::time::current_epoch().to_string() };
+    (human) => { $crate::time::date_human() };
+    (iso) => { $crate::time::date_iso() };
+    ($fmt:expr) => { $crate::time::date_format(&$fmt) };
+    () => { $crate::time::date_human() };
+}
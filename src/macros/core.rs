@@ -0,0 +1,35 @@
+// Application bootstrap and argument access.
+
+#[macro_export]
+macro_rules! bootstrap {
+    () => {{
+        let __args: Vec<String> = std::env::args().collect();
+        $crate::context::rsb_bootstrap(&__args);
+        __args
+    }};
+}
+
+#[macro_export]
+macro_rules! args {
+    () => {
+        std::env::args().collect::<Vec<String>>()
+    };
+}
+
+// Maps --flags onto opt_* context vars
+#[macro_export]
+macro_rules! options {
+    ($args:expr) => {
+        $crate::args::apply_options(&$crate::args::Args::new($args))
+    };
+}
+
+// Set vars (context and process env) for the duration of a block; prior
+// values are restored afterwards, even on early return
+#[macro_export]
+macro_rules! env_guard {
+    ({ $($key:expr => $value:expr),* $(,)? } => $body:block) => {{
+        let __guard = $crate::context::VarGuard::set(&[$((&$key[..], &$value[..])),*]);
+        $body
+    }};
+}
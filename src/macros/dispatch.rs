@@ -0,0 +1,132 @@
+// Command routing. Handlers are `fn(Args) -> i32`; each call emits the
+// `command_start`/`command_end` events (see context::call_handler).
+
+// Routes to a handler, then exits with its code. A trailing `_ => handler`
+// arm receives every argument (command included) when nothing else
+// matches; without it unknown commands print help and exit 1.
+//
+// An optional `global: { ... }` block is applied and stripped from the args
+// first, wherever it appears: `"--verbose" => set DEBUG_MODE` sets a var to
+// "1", `"--config" => load_config_file` calls a `fn(&str)` with the value.
+#[macro_export]
+macro_rules! dispatch {
+    ($args:expr, global: { $($globals:tt)* }, { $($routes:tt)* }) => {{
+        let __code: i32 = $crate::try_dispatch!($args, global: { $($globals)* }, { $($routes)* });
+        $crate::context::emit_exit(__code);
+        std::process::exit(__code);
+    }};
+    ($args:expr, { $($routes:tt)* }) => {{
+        let __code: i32 = $crate::try_dispatch!($args, { $($routes)* });
+        $crate::context::emit_exit(__code);
+        std::process::exit(__code);
+    }};
+}
+
+// Same routing as dispatch! but returns the exit code instead of exiting
+#[macro_export]
+macro_rules! try_dispatch {
+    ($args:expr, global: { $($flag:literal => $action:ident $($var:ident)?),* $(,)? }, { $($routes:tt)* }) => {{
+        let mut __global_argv: Vec<String> = ($args).to_vec();
+        $( $crate::__global_option!(__global_argv, $flag, $action $($var)?); )*
+        $crate::try_dispatch!(&__global_argv, { $($routes)* })
+    }};
+    ($args:expr, { $($cmd:literal => $handler:ident),* , _ => $default:ident $(,)? }) => {
+        $crate::try_dispatch!(@route $args, { $($cmd => $handler),* }, |_: &str, __argv: &[String]| {
+            let __all = $crate::args::Args::new(__argv.get(1..).unwrap_or(&[]));
+            $crate::context::call_handler(stringify!($default), __all, $default)
+        })
+    };
+    ($args:expr, { $($cmd:literal => $handler:ident),* $(,)? }) => {
+        $crate::try_dispatch!(@route $args, { $($cmd => $handler),* }, |__command: &str, _: &[String]| {
+            $crate::error!("Unknown command: {}", __command);
+            $crate::context::show_help();
+            1
+        })
+    };
+    (@route $args:expr, { $($cmd:literal => $handler:ident),* }, $fallback:expr) => {{
+        let __argv: &[String] = $args;
+        let __command = __argv.get(1).cloned().unwrap_or_else(|| "help".to_string());
+        let __cmd_args = $crate::args::Args::new(__argv.get(2..).unwrap_or(&[]));
+
+        $( $crate::context::register_function($cmd, stringify!($handler)); )*
+
+        let __code: i32 = match __command.as_str() {
+            $(
+                $cmd => $crate::context::call_handler($cmd, __cmd_args, $handler),
+            )*
+            "help" | "--help" | "-h" => {
+                match __argv.get(2) {
+                    Some(__topic) => $crate::context::show_command_help(__topic),
+                    None => $crate::context::show_help(),
+                }
+                0
+            }
+            "inspect" => {
+                $crate::context::show_functions();
+                0
+            }
+            "stack" => {
+                $crate::context::show_call_stack();
+                0
+            }
+            _ => {
+                let __fallback = $fallback;
+                __fallback(__command.as_str(), __argv)
+            }
+        };
+        __code
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __global_option {
+    ($argv:ident, $flag:literal, set $var:ident) => {
+        if $crate::args::take_global(&mut $argv, $flag, false).is_some() {
+            $crate::context::set_var(stringify!($var), "1");
+        }
+    };
+    ($argv:ident, $flag:literal, $func:ident) => {
+        if let Some(__value) = $crate::args::take_global(&mut $argv, $flag, true) {
+            $func(&__value);
+        }
+    };
+}
+
+// Register usage lines for `help <command>`
+#[macro_export]
+macro_rules! usage {
+    ($($cmd:literal => $usage:expr),+ $(,)?) => {
+        $( $crate::context::register_usage($cmd, &$usage); )+
+    };
+}
+
+// Print the usage for one command (general help if it has none)
+#[macro_export]
+macro_rules! dispatch_help {
+    ($cmd:expr) => {
+        $crate::context::show_command_help(&$cmd)
+    };
+}
+
+// Runs bootstrap-time commands (install, init) before config is loaded.
+// Returns whether a pre-command ran; it never exits, so the caller decides
+// what happens next (typically `if pre_dispatch!(...) { return; }`).
+#[macro_export]
+macro_rules! pre_dispatch {
+    ($args:expr, { $($cmd:literal => $handler:ident),* $(,)? }) => {{
+        let __argv: &[String] = $args;
+        let __command = __argv.get(1).cloned().unwrap_or_default();
+        let __cmd_args = $crate::args::Args::new(__argv.get(2..).unwrap_or(&[]));
+
+        match __command.as_str() {
+            $(
+                $cmd => {
+                    $crate::context::call_handler($cmd, __cmd_args, $handler);
+                    true
+                }
+            )*
+            _ => false,
+        }
+    }};
+}
@@ -0,0 +1,271 @@
+// File system, config, temp file and archive macros.
+
+#[macro_export]
+macro_rules! cp {
+    ($src:expr, $dest:expr, recursive) => { $crate::fs::cp_r(&$src, &$dest) };
+    ($src:expr, $dest:expr) => { $crate::fs::cp(&$src, &$dest) };
+}
+
+#[macro_export]
+macro_rules! mv {
+    ($src:expr, $dest:expr) => { $crate::fs::mv(&$src, &$dest) };
+}
+
+#[macro_export]
+macro_rules! rm {
+    ($path:expr, recursive) => { $crate::fs::rm_rf(&$path) };
+    ($path:expr) => { $crate::fs::rm(&$path) };
+}
+
+#[macro_export]
+macro_rules! mkdir_p {
+    ($path:expr) => { $crate::fs::mkdir_p(&$path) };
+}
+
+#[macro_export]
+macro_rules! touch {
+    ($path:expr) => { $crate::fs::touch(&$path) };
+}
+
+#[macro_export]
+macro_rules! chmod {
+    ($path:expr, $mode:expr, recursive) => { $crate::fs::chmod_recursive(&$path, &$mode) };
+    ($path:expr, $mode:expr) => { $crate::fs::chmod(&$path, &$mode) };
+}
+
+// chown!(path, "user", "group"[, recursive]); "" leaves that part unchanged
+#[cfg(unix)]
+#[macro_export]
+macro_rules! chown {
+    ($path:expr, $user:expr, $group:expr, recursive) => {
+        $crate::fs::chown_recursive(&$path, &$user, &$group)
+    };
+    ($path:expr, $user:expr, $group:expr) => { $crate::fs::chown(&$path, &$user, &$group) };
+}
+
+#[macro_export]
+macro_rules! backup {
+    ($path:expr, keep: $n:expr) => { $crate::fs::backup_rotated(&$path, $n) };
+    ($path:expr, timestamp) => { $crate::fs::backup_timestamped(&$path) };
+    ($path:expr, $suffix:expr) => { $crate::fs::backup_file(&$path, &$suffix) };
+    ($path:expr) => { $crate::fs::backup_file(&$path, ".bak") };
+}
+
+#[macro_export]
+macro_rules! path_split {
+    ($path:expr, into: $name:expr) => { $crate::fs::path_split(&$path, &$name) };
+}
+
+// for f in dir/*
+#[macro_export]
+macro_rules! file_in {
+    ($var:ident in $dir:expr => $body:block) => {
+        for $var in $crate::fs::list_dir(&$dir) $body
+    };
+}
+
+// Read `# @key: value` headers
+#[macro_export]
+macro_rules! meta_key {
+    ($path:expr, $key:expr) => {
+        $crate::fs::extract_meta_from_file(&$path)
+            .get(&$key.to_string())
+            .cloned()
+            .unwrap_or_default()
+    };
+}
+
+#[macro_export]
+macro_rules! meta_keys {
+    ($path:expr, into: $prefix:expr) => {
+        for (__k, __v) in $crate::fs::extract_meta_from_file(&$path) {
+            $crate::context::set_var(format!("{}_{}", $prefix, __k), __v);
+        }
+    };
+    ($path:expr) => {
+        $crate::fs::extract_meta_from_file(&$path)
+    };
+}
+
+#[macro_export]
+macro_rules! meta_set {
+    ($path:expr, $key:expr, $value:expr) => {
+        $crate::fs::set_meta(&$path, &$key, &$value)
+    };
+}
+
+// --- Temp files / process substitution ---------------------------------------------
+
+#[macro_export]
+macro_rules! tmp {
+    (pid) => { $crate::fs::create_temp_file_path("pid") };
+    (random) => { $crate::fs::create_temp_file_path("random") };
+    () => { $crate::fs::create_temp_file_path("timestamp") };
+}
+
+#[macro_export]
+macro_rules! cap_stream {
+    ($stream:expr) => { $crate::fs::capture_stream_to_temp_file($stream) };
+}
+
+// <(...) process substitution: a temp file path holding the stream,
+// or any string with `content:`
+#[macro_export]
+macro_rules! subst {
+    (content: $content:expr) => { $crate::os::temp_with_content(&$content) };
+    ($stream:expr) => { $crate::fs::capture_stream_to_temp_file($stream) };
+}
+
+// --- Config --------------------------------------------------------------------------
+
+#[macro_export]
+macro_rules! load_config {
+    ($($path:expr),+ $(,)?) => {
+        $( $crate::context::load_config_file(&$path); )+
+    };
+}
+
+// Loads `name` from /etc, $XDG_ETC, ~/.config/$SCRIPT_NAME and ./ in that
+// order (later wins); returns the loaded paths
+#[macro_export]
+macro_rules! load_config_search {
+    ($name:expr) => { $crate::context::load_config_search(&$name) };
+}
+
+// `source file.conf`
+#[macro_export]
+macro_rules! src {
+    ($($path:expr),+ $(,)?) => {
+        $( $crate::context::load_config_file(&$path); )+
+    };
+}
+
+// Tiny persistent key-value store in a config-style file
+#[macro_export]
+macro_rules! kv {
+    (open: $path:expr) => { $crate::context::kv_open(&$path) };
+    (set: $key:expr => $value:expr) => { $crate::context::kv_set(&$key, &$value) };
+    (get: $key:expr) => { $crate::context::kv_get(&$key) };
+    (del: $key:expr) => { $crate::context::kv_del(&$key) };
+}
+
+// Like `set -a; source .env`: context vars plus real environment variables
+#[macro_export]
+macro_rules! source_env {
+    ($($path:expr),+ $(,)?) => {
+        $( $crate::context::source_env_file(&$path); )+
+    };
+}
+
+// Rerun a block whenever files matching a glob change (feature "watch").
+// Debounced (default 200ms), stops on Ctrl-C or after `runs:` reruns.
+#[cfg(feature = "watch")]
+#[macro_export]
+macro_rules! watch {
+    ($pattern:expr, debounce: $ms:expr, runs: $n:expr => $body:block) => {
+        $crate::fs::watch(&$pattern, $ms, Some($n), || { let _ = $body; })
+    };
+    ($pattern:expr, runs: $n:expr => $body:block) => {
+        $crate::watch!($pattern, debounce: 200, runs: $n => $body)
+    };
+    ($pattern:expr, debounce: $ms:expr => $body:block) => {
+        $crate::fs::watch(&$pattern, $ms, None, || { let _ = $body; })
+    };
+    ($pattern:expr => $body:block) => {
+        $crate::watch!($pattern, debounce: 200 => $body)
+    };
+}
+
+#[macro_export]
+macro_rules! export {
+    ($path:expr) => { $crate::context::export_vars(&$path) };
+}
+
+// --- Archives -------------------------------------------------------------------------
+
+#[macro_export]
+macro_rules! tar {
+    (create: $archive:expr, $($path:expr),+ $(,)?) => {
+        $crate::__archive_ok!($crate::os::create_tar(&$archive, &[$(&*$path.to_string()),+]))
+    };
+    (extract: $archive:expr, to: $dest:expr) => {
+        $crate::__archive_ok!($crate::os::extract_tar(&$archive, Some(&$dest)))
+    };
+    (extract: $archive:expr) => {
+        $crate::__archive_ok!($crate::os::extract_tar(&$archive, None))
+    };
+    (list: $archive:expr) => {
+        $crate::__archive_ok!($crate::os::list_tar(&$archive))
+    };
+}
+
+#[macro_export]
+macro_rules! zip {
+    (create: $archive:expr, $($path:expr),+ $(,)?) => {
+        $crate::__archive_ok!($crate::os::create_zip(&$archive, &[$(&*$path.to_string()),+]))
+    };
+    (extract: $archive:expr, to: $dest:expr) => {
+        $crate::__archive_ok!($crate::os::extract_zip(&$archive, Some(&$dest)))
+    };
+    (extract: $archive:expr) => {
+        $crate::__archive_ok!($crate::os::extract_zip(&$archive, None))
+    };
+    (list: $archive:expr) => {
+        $crate::__archive_ok!($crate::os::list_zip(&$archive))
+    };
+}
+
+// Create an archive, picking the format from the extension
+#[macro_export]
+macro_rules! pack {
+    ($archive:expr, $($path:expr),+ $(,)?) => {{
+        let __archive = $archive.to_string();
+        let __paths: Vec<String> = vec![$($path.to_string()),+];
+        let __refs: Vec<&str> = __paths.iter().map(String::as_str).collect();
+        let __result = if __archive.ends_with(".zip") {
+            $crate::os::create_zip(&__archive, &__refs)
+        } else if __archive.ends_with(".tar.gz") || __archive.ends_with(".tgz") {
+            $crate::os::create_tar_gz(&__archive, &__refs)
+        } else {
+            $crate::os::create_tar(&__archive, &__refs)
+        };
+        $crate::__archive_ok!(__result)
+    }};
+}
+
+#[macro_export]
+macro_rules! unpack {
+    ($archive:expr, to: $dest:expr) => {{
+        let __archive = $archive.to_string();
+        let __result = if __archive.ends_with(".zip") {
+            $crate::os::extract_zip(&__archive, Some(&$dest))
+        } else {
+            $crate::os::extract_tar(&__archive, Some(&$dest))
+        };
+        $crate::__archive_ok!(__result)
+    }};
+    ($archive:expr) => {{
+        let __archive = $archive.to_string();
+        let __result = if __archive.ends_with(".zip") {
+            $crate::os::extract_zip(&__archive, None)
+        } else {
+            $crate::os::extract_tar(&__archive, None)
+        };
+        $crate::__archive_ok!(__result)
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __archive_ok {
+    ($result:expr) => {{
+        let __r: $crate::os::CmdResult = $result;
+        match __r.into_result("archive operation") {
+            Ok(__output) => __output,
+            Err(__e) => {
+                $crate::error!("{}", __e);
+                std::process::exit(__e.status());
+            }
+        }
+    }};
+}
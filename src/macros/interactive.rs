@@ -0,0 +1,52 @@
+// Prompts and confirmations (prompts are drawn on stderr).
+
+#[macro_export]
+macro_rules! confirm {
+    ($msg:expr, default: $default:expr) => { $crate::utils::confirm_action(&$msg, Some($default)) };
+    ($msg:expr) => { $crate::utils::confirm_action(&$msg, None) };
+}
+
+// Yes/no that falls back to `default` after a countdown of `secs`
+#[macro_export]
+macro_rules! confirm_timeout {
+    ($msg:expr, secs: $secs:expr, default: $default:expr) => {
+        $crate::utils::confirm_timeout(&$msg, $secs, $default)
+    };
+}
+
+// Requires typing a confirmation word (default "yes"); only --force bypasses it
+#[macro_export]
+macro_rules! confirm_destructive {
+    ($msg:expr, expected: $word:expr) => { $crate::utils::confirm_destructive(&$msg, &$word) };
+    ($msg:expr) => { $crate::utils::confirm_destructive(&$msg, "yes") };
+}
+
+#[macro_export]
+macro_rules! prompt {
+    ($msg:expr, default: $default:expr) => { $crate::utils::prompt_user(&$msg, Some(&$default)) };
+    ($msg:expr) => { $crate::utils::prompt_user(&$msg, None) };
+}
+
+// Several lines of input, ended by EOF or a "." line (or `until:` sentinel);
+// `editor` opens $VISUAL/$EDITOR on a temp file when attached to a terminal
+#[macro_export]
+macro_rules! prompt_multiline {
+    ($msg:expr, editor) => { $crate::utils::prompt_multiline(&$msg, ".", true) };
+    ($msg:expr, until: $sentinel:expr) => { $crate::utils::prompt_multiline(&$msg, &$sentinel, false) };
+    ($msg:expr) => { $crate::utils::prompt_multiline(&$msg, ".", false) };
+    () => { $crate::utils::prompt_multiline("", ".", false) };
+}
+
+// readline!(prompt, timeout: secs, default: "y") gives up waiting after secs
+#[macro_export]
+macro_rules! readline {
+    ($msg:expr, timeout: $secs:expr, default: $default:expr) => {{
+        eprint!("{}", $msg);
+        $crate::utils::readline_timeout($secs, &$default)
+    }};
+    ($msg:expr) => {{
+        eprint!("{}", $msg);
+        $crate::utils::readline()
+    }};
+    () => { $crate::utils::readline() };
+}
@@ -0,0 +1,15 @@
+//! All RSB macros. Every macro is `#[macro_export]`ed to the crate root and
+//! re-exported through the prelude.
+
+mod control;
+mod core;
+mod dispatch;
+mod fs_data;
+mod interactive;
+mod os;
+mod random;
+mod stderr;
+mod streams_exec;
+mod text;
+mod validation;
+mod visual;
@@ -0,0 +1,204 @@
+// System info, processes, locks, network and jobs.
+
+#[macro_export]
+macro_rules! hostname {
+    () => { $crate::os::get_hostname() };
+}
+
+#[macro_export]
+macro_rules! fqdn {
+    () => { $crate::os::get_fqdn() };
+}
+
+// Non-loopback interface addresses (loopback if there are none)
+#[macro_export]
+macro_rules! local_ips {
+    () => { $crate::os::get_local_ips() };
+}
+
+#[macro_export]
+macro_rules! user {
+    () => { $crate::os::get_username() };
+}
+
+#[macro_export]
+macro_rules! home_dir {
+    () => { $crate::os::get_home_dir() };
+}
+
+#[macro_export]
+macro_rules! current_dir {
+    () => { $crate::os::get_current_dir() };
+}
+
+#[macro_export]
+macro_rules! os_release {
+    () => { $crate::os::get_os_release() };
+}
+
+#[macro_export]
+macro_rules! mem {
+    () => { $crate::os::get_memory() };
+}
+
+#[macro_export]
+macro_rules! loadavg {
+    () => { $crate::os::get_load_average() };
+}
+
+#[macro_export]
+macro_rules! uptime {
+    () => { $crate::os::get_uptime() };
+}
+
+#[macro_export]
+macro_rules! cpu_count {
+    () => { $crate::os::cpu_count() };
+}
+
+#[macro_export]
+macro_rules! pid_of {
+    ($name:expr) => { $crate::os::pid_of(&$name) };
+}
+
+#[macro_export]
+macro_rules! pids_of {
+    ($name:expr) => { $crate::os::pids_of(&$name) };
+}
+
+#[macro_export]
+macro_rules! process_exists {
+    ($name:expr) => { $crate::os::process_exists(&$name) };
+}
+
+#[macro_export]
+macro_rules! kill_pid {
+    ($pid:expr, signal: $sig:expr) => { $crate::os::kill_pid(&$pid, Some(&$sig)) };
+    ($pid:expr) => { $crate::os::kill_pid(&$pid, None) };
+}
+
+// signal!(pid, "HUP") -> RsbResult<()>, sent natively with kill(2)
+#[cfg(unix)]
+#[macro_export]
+macro_rules! signal {
+    ($pid:expr, $sig:expr) => { $crate::os::send_signal($pid, &$sig) };
+}
+
+#[macro_export]
+macro_rules! kill_process {
+    ($name:expr, signal: $sig:expr) => { $crate::os::kill_process(&$name, Some(&$sig)) };
+    ($name:expr) => { $crate::os::kill_process(&$name, None) };
+}
+
+// --- Locks -------------------------------------------------------------------------
+
+#[macro_export]
+macro_rules! lock {
+    ($path:expr) => { $crate::os::create_lock(&$path) };
+}
+
+#[macro_export]
+macro_rules! unlock {
+    ($path:expr) => { $crate::os::release_lock(&$path) };
+}
+
+#[macro_export]
+macro_rules! with_lock {
+    ($path:expr => $body:block) => {{
+        $crate::os::create_lock(&$path);
+        let __result = $body;
+        $crate::os::release_lock(&$path);
+        __result
+    }};
+}
+
+// --- Network & JSON ------------------------------------------------------------------
+
+#[macro_export]
+macro_rules! curl {
+    (post: $url:expr, data: $data:expr) => { $crate::os::http_post(&$url, &$data) };
+    ($url:expr, options: $opts:expr) => { $crate::os::curl(&$url, &$opts) };
+    ($url:expr) => { $crate::os::http_get(&$url) };
+}
+
+// GET with retries on 5xx/connection errors only; RsbResult<String> body
+#[macro_export]
+macro_rules! retry_http {
+    ($url:expr, attempts: $n:expr, delay: $delay_ms:expr) => { $crate::os::retry_http(&$url, $n, $delay_ms) };
+    ($url:expr, attempts: $n:expr) => { $crate::os::retry_http(&$url, $n, 1000) };
+}
+
+#[macro_export]
+macro_rules! get {
+    ($url:expr) => { $crate::os::http_get(&$url) };
+}
+
+#[macro_export]
+macro_rules! json_get {
+    ($json:expr, $path:expr) => { $crate::os::json_get(&$json, &$path) };
+}
+
+#[macro_export]
+macro_rules! json_get_file {
+    ($file:expr, $path:expr) => { $crate::os::json_get_file(&$file, &$path) };
+}
+
+// --- Jobs & events ---------------------------------------------------------------------
+
+#[macro_export]
+macro_rules! job {
+    (background: $cmd:expr) => { $crate::os::start_job(&$cmd) };
+    (timeout: $secs:expr, wait: $id:expr) => { $crate::os::wait_on_job($id, Some($secs)) };
+    (wait: $id:expr) => { $crate::os::wait_on_job($id, None) };
+    (list) => { $crate::os::list_jobs() };
+}
+
+// Register a handler for an event such as "EXIT"
+#[macro_export]
+macro_rules! trap {
+    ($handler:expr, on: $event:expr) => {
+        $crate::context::trap_handler(&$event, $handler)
+    };
+}
+
+#[macro_export]
+macro_rules! event {
+    (emit: $event:expr, $($key:expr => $value:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut __data = std::collections::HashMap::new();
+        $( __data.insert($key.to_string(), $value.to_string()); )*
+        $crate::context::emit_event(&$event, &__data)
+    }};
+}
+
+// Open a file or URL with the default application; returns the CmdResult
+#[macro_export]
+macro_rules! open {
+    ($target:expr) => {
+        $crate::os::open(&$target)
+    };
+}
+
+// Quote a value for safe interpolation into a shell command string
+#[macro_export]
+macro_rules! sh_quote {
+    ($value:expr) => {
+        $crate::os::sh_quote(&$value)
+    };
+}
+
+// Hand off to another program, like shell `exec`; exits 127 if it can't start
+#[macro_export]
+macro_rules! exec {
+    ($program:expr, args: $args:expr) => {{
+        let __args: Vec<&str> = $args.iter().map(|a| a.as_ref()).collect();
+        let __err = $crate::os::exec(&$program, &__args);
+        $crate::error!("exec failed: {}", __err);
+        std::process::exit(127)
+    }};
+    ($program:expr $(, $arg:expr)* $(,)?) => {{
+        let __err = $crate::os::exec(&$program, &[$(AsRef::<str>::as_ref(&$arg)),*]);
+        $crate::error!("exec failed: {}", __err);
+        std::process::exit(127)
+    }};
+}
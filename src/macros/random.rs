@@ -0,0 +1,64 @@
+// Random values and word dictionaries.
+
+#[macro_export]
+macro_rules! rand_alnum {
+    ($n:expr) => { $crate::random::get_rand_alnum($n) };
+}
+
+#[macro_export]
+macro_rules! rand_alpha {
+    ($n:expr) => { $crate::random::get_rand_alpha($n) };
+}
+
+#[macro_export]
+macro_rules! rand_hex {
+    ($n:expr) => { $crate::random::get_rand_hex($n) };
+}
+
+#[macro_export]
+macro_rules! rand_string {
+    ($n:expr) => { $crate::random::get_rand_string($n) };
+}
+
+#[macro_export]
+macro_rules! rand_uuid {
+    () => { $crate::random::get_rand_uuid() };
+}
+
+#[macro_export]
+macro_rules! rand_range {
+    ($min:expr, $max:expr) => { $crate::random::get_rand_range($min, $max) };
+}
+
+// Load a wordlist file, optionally into a context array
+#[macro_export]
+macro_rules! dict {
+    ($path:expr, into: $name:expr) => {{
+        let __words = $crate::random::load_dict(&$path);
+        let __refs: Vec<&str> = __words.iter().map(String::as_str).collect();
+        $crate::utils::set_array(&$name, &__refs);
+    }};
+    ($path:expr) => { $crate::random::load_dict(&$path) };
+}
+
+// gen_dict!(alnum|alpha|hex|string|words|syllable, count, into: "ARR");
+// gen_dict!(words, count, from: "wordlist.txt", into: "ARR")
+#[macro_export]
+macro_rules! gen_dict {
+    (words, $count:expr, from: $path:expr, into: $name:expr) => {
+        $crate::random::gen_dict_from(&$path, $count, &$name)
+    };
+    ($kind:ident, $count:expr, into: $name:expr) => {
+        $crate::random::gen_dict(stringify!($kind), $count, &$name)
+    };
+}
+
+// Random word(s) from a context array; `unique` samples without repeats
+#[macro_export]
+macro_rules! rand_dict {
+    ($name:expr, $n:expr, unique, $delim:expr) => { $crate::random::rand_dict_unique(&$name, $n, &$delim) };
+    ($name:expr, $n:expr, unique) => { $crate::random::rand_dict_unique(&$name, $n, " ") };
+    ($name:expr, $n:expr, $delim:expr) => { $crate::random::rand_dict(&$name, $n, &$delim) };
+    ($name:expr, $n:expr) => { $crate::random::rand_dict(&$name, $n, " ") };
+    ($name:expr) => { $crate::random::rand_dict(&$name, 1, " ") };
+}
@@ -0,0 +1,80 @@
+// Leveled stderr messages. A single argument is printed as-is (so `{red}`
+// color tags work); with format arguments it goes through `format!` first.
+
+#[macro_export]
+macro_rules! info {
+    ($fmt:literal, $($arg:tt)+) => { $crate::utils::stderrx("info", &format!($fmt, $($arg)+)) };
+    ($msg:expr) => { $crate::utils::stderrx("info", &$msg.to_string()) };
+}
+
+#[macro_export]
+macro_rules! okay {
+    ($fmt:literal, $($arg:tt)+) => { $crate::utils::stderrx("okay", &format!($fmt, $($arg)+)) };
+    ($msg:expr) => { $crate::utils::stderrx("okay", &$msg.to_string()) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($fmt:literal, $($arg:tt)+) => { $crate::utils::stderrx("warn", &format!($fmt, $($arg)+)) };
+    ($msg:expr) => { $crate::utils::stderrx("warn", &$msg.to_string()) };
+}
+
+#[macro_export]
+macro_rules! error {
+    ($fmt:literal, $($arg:tt)+) => { $crate::utils::stderrx("error", &format!($fmt, $($arg)+)) };
+    ($msg:expr) => { $crate::utils::stderrx("error", &$msg.to_string()) };
+}
+
+#[macro_export]
+macro_rules! fatal {
+    ($fmt:literal, $($arg:tt)+) => { $crate::utils::stderrx("fatal", &format!($fmt, $($arg)+)) };
+    ($msg:expr) => { $crate::utils::stderrx("fatal", &$msg.to_string()) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($fmt:literal, $($arg:tt)+) => { $crate::utils::stderrx("debug", &format!($fmt, $($arg)+)) };
+    ($msg:expr) => { $crate::utils::stderrx("debug", &$msg.to_string()) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($fmt:literal, $($arg:tt)+) => { $crate::utils::stderrx("trace", &format!($fmt, $($arg)+)) };
+    ($msg:expr) => { $crate::utils::stderrx("trace", &$msg.to_string()) };
+}
+
+// stdout output with variable and color expansion
+#[macro_export]
+macro_rules! echo {
+    () => { $crate::utils::write_out("\n") };
+    ($fmt:literal, $($arg:tt)+) => {
+        $crate::utils::write_out(&format!("{}\n", $crate::utils::expand_colors(&$crate::context::expand_vars(&format!($fmt, $($arg)+)))))
+    };
+    ($msg:expr) => {
+        $crate::utils::write_out(&format!("{}\n", $crate::utils::expand_colors(&$crate::context::expand_vars(&$msg.to_string()))))
+    };
+}
+
+// A full-width header rule around a title, on stderr
+#[macro_export]
+macro_rules! section {
+    ($fmt:literal, $($arg:tt)+) => { $crate::utils::section(&format!($fmt, $($arg)+)) };
+    ($title:expr) => { $crate::utils::section(&$title.to_string()) };
+}
+
+// quiet! { ... } drops stdout and non-error messages from the block
+#[macro_export]
+macro_rules! quiet {
+    ($($body:tt)*) => { $crate::utils::quietly(|| { $($body)* }) };
+}
+
+// Like echo! without the trailing newline
+#[macro_export]
+macro_rules! printf {
+    ($fmt:literal, $($arg:tt)+) => {
+        $crate::utils::write_out(&$crate::utils::expand_colors(&$crate::context::expand_vars(&format!($fmt, $($arg)+))))
+    };
+    ($msg:expr) => {
+        $crate::utils::write_out(&$crate::utils::expand_colors(&$crate::context::expand_vars(&$msg.to_string())))
+    };
+}
@@ -0,0 +1,100 @@
+// Stream sources and command execution.
+
+#[macro_export]
+macro_rules! cat {
+    ($($file:expr),+ $(,)?) => {{
+        let __paths: Vec<String> = vec![$($file.to_string()),+];
+        let __refs: Vec<&str> = __paths.iter().map(String::as_str).collect();
+        $crate::streams::Stream::from_files(&__refs)
+    }};
+}
+
+#[macro_export]
+macro_rules! cmd {
+    ($fmt:literal, $($arg:tt)+) => {
+        $crate::cmd!(format!($fmt, $($arg)+))
+    };
+    ($command:expr) => {
+        $crate::__unless_dry_run!($command, $crate::streams::Stream::new(), $crate::streams::Stream::from_cmd)
+    };
+}
+
+#[macro_export]
+macro_rules! pipe {
+    ($input:expr) => {
+        $crate::streams::Stream::from_string(&$input.to_string())
+    };
+}
+
+#[macro_export]
+macro_rules! stream {
+    (var: $name:expr) => {
+        $crate::streams::Stream::from_var(&$name)
+    };
+    (array: $items:expr) => {
+        $crate::streams::Stream::from_vec($items)
+    };
+    (lines: $lines:expr) => {
+        <$crate::streams::Stream as std::iter::FromIterator<_>>::from_iter($lines)
+    };
+    (string: $content:expr) => {
+        $crate::streams::Stream::from_string(&$content)
+    };
+    (file: $path:expr) => {
+        $crate::streams::Stream::from_file(&$path)
+    };
+    (cmd: $command:expr) => {
+        $crate::streams::Stream::from_cmd(&$command)
+    };
+    (delimited: $content:expr, on: $delim:expr) => {
+        $crate::streams::Stream::from_delimited_string(&$content, &$delim)
+    };
+}
+
+// stdout of a command; exits on failure unless `silent`. `spinner:` shows
+// a message with a spinner on stderr while it runs. Under dry-run (see
+// context::dry_run) run!/shell!/cmd! log the command and return empty output.
+#[macro_export]
+macro_rules! run {
+    ($command:expr, spinner: $message:expr) => {
+        $crate::__unless_dry_run!($command, String::new(), |__cmd| $crate::os::run_cmd_spinner(__cmd, &$message))
+    };
+    ($command:expr, silent) => {
+        $crate::__unless_dry_run!($command, String::new(), |__cmd| $crate::os::shell_exec(__cmd, true))
+    };
+    ($command:expr) => {
+        $crate::__unless_dry_run!($command, String::new(), $crate::os::run_cmd)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __unless_dry_run {
+    ($command:expr, $skipped:expr, $run:expr) => {{
+        let __cmd: &str = &$command;
+        if $crate::context::dry_run_skip(&format!("run: {}", __cmd)) {
+            $skipped
+        } else {
+            ($run)(__cmd)
+        }
+    }};
+}
+
+// Full CmdResult for manual error handling, or stdout with `silent`
+#[macro_export]
+macro_rules! shell {
+    ($command:expr, silent) => {
+        $crate::__unless_dry_run!($command, String::new(), |__cmd| $crate::os::shell_exec(__cmd, true))
+    };
+    ($command:expr) => {
+        $crate::__unless_dry_run!($command, $crate::os::CmdResult::default(), $crate::os::run_cmd_with_status)
+    };
+}
+
+// Shell `tee`: copy a stream to a file, stdout or stderr and pass it on
+#[macro_export]
+macro_rules! tee {
+    ($stream:expr, stdout) => { $stream.tee_stdout() };
+    ($stream:expr, stderr) => { $stream.tee_stderr() };
+    ($stream:expr, $path:expr) => { $stream.tee(&$path) };
+}
@@ -0,0 +1,312 @@
+// Parameter expansion and text manipulation.
+
+// ${VAR...} style expansions over context variables
+#[macro_export]
+macro_rules! param {
+    ($var:expr, default: $default:expr) => {{
+        let __v = $crate::context::get_var(&$var);
+        if __v.is_empty() { $default.to_string() } else { __v }
+    }};
+    ($var:expr, alt: $alt:expr) => {{
+        let __v = $crate::context::get_var(&$var);
+        if __v.is_empty() { String::new() } else { $alt.to_string() }
+    }};
+    ($var:expr, len) => {
+        $crate::context::get_var(&$var).chars().count()
+    };
+    ($var:expr, sub: $offset:expr, $len:expr) => {
+        $crate::utils::str_sub(&$crate::context::get_var(&$var), $offset as isize, Some($len))
+    };
+    ($var:expr, sub: $offset:expr) => {
+        $crate::utils::str_sub(&$crate::context::get_var(&$var), $offset as isize, None)
+    };
+    ($var:expr, prefix: $pattern:expr, longest) => {
+        $crate::utils::str_prefix(&$crate::context::get_var(&$var), &$pattern, true)
+    };
+    ($var:expr, prefix: $pattern:expr) => {
+        $crate::utils::str_prefix(&$crate::context::get_var(&$var), &$pattern, false)
+    };
+    ($var:expr, suffix: $pattern:expr, longest) => {
+        $crate::utils::str_suffix(&$crate::context::get_var(&$var), &$pattern, true)
+    };
+    ($var:expr, suffix: $pattern:expr) => {
+        $crate::utils::str_suffix(&$crate::context::get_var(&$var), &$pattern, false)
+    };
+    ($var:expr, replace: $from:expr => $to:expr, all) => {
+        $crate::utils::str_replace(&$crate::context::get_var(&$var), &$from, &$to, true)
+    };
+    ($var:expr, replace: $from:expr => $to:expr) => {
+        $crate::utils::str_replace(&$crate::context::get_var(&$var), &$from, &$to, false)
+    };
+    ($var:expr, upper: first) => {
+        $crate::utils::str_upper(&$crate::context::get_var(&$var), true)
+    };
+    ($var:expr, upper) => {
+        $crate::utils::str_upper(&$crate::context::get_var(&$var), false)
+    };
+    ($var:expr, lower: first) => {
+        $crate::utils::str_lower(&$crate::context::get_var(&$var), true)
+    };
+    ($var:expr, lower) => {
+        $crate::utils::str_lower(&$crate::context::get_var(&$var), false)
+    };
+    ($var:expr) => {
+        $crate::context::get_var(&$var)
+    };
+}
+
+// Split a string into a context array
+#[macro_export]
+macro_rules! str_explode {
+    ($value:expr, on: $delim:expr, into: $name:expr) => {
+        $crate::utils::str_explode(&$value, &$delim, &$name)
+    };
+}
+
+#[macro_export]
+macro_rules! str_join {
+    ($name:expr, with: $delim:expr) => {
+        $crate::utils::str_join(&$name, &$delim)
+    };
+}
+
+// Set operations on two context arrays: a Vec, or stored with `into:`
+#[macro_export]
+macro_rules! array_union {
+    ($a:expr, $b:expr, into: $name:expr) => {
+        $crate::__store_array!($name, $crate::utils::array_union(&$a, &$b))
+    };
+    ($a:expr, $b:expr) => { $crate::utils::array_union(&$a, &$b) };
+}
+
+#[macro_export]
+macro_rules! array_intersect {
+    ($a:expr, $b:expr, into: $name:expr) => {
+        $crate::__store_array!($name, $crate::utils::array_intersect(&$a, &$b))
+    };
+    ($a:expr, $b:expr) => { $crate::utils::array_intersect(&$a, &$b) };
+}
+
+#[macro_export]
+macro_rules! array_difference {
+    ($a:expr, $b:expr, into: $name:expr) => {
+        $crate::__store_array!($name, $crate::utils::array_difference(&$a, &$b))
+    };
+    ($a:expr, $b:expr) => { $crate::utils::array_difference(&$a, &$b) };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __store_array {
+    ($name:expr, $items:expr) => {{
+        let __items: Vec<String> = $items;
+        let __refs: Vec<&str> = __items.iter().map(String::as_str).collect();
+        $crate::utils::set_array(&$name, &__refs);
+    }};
+}
+
+// Encoding: decode returns the input unchanged on failure, or a Result with `strict`
+#[macro_export]
+macro_rules! b64 {
+    (decode: $value:expr, strict) => {
+        $crate::utils::base64_decode(&$value)
+    };
+    (decode: $value:expr) => {
+        $crate::utils::base64_decode(&$value).unwrap_or_else(|_| $value.to_string())
+    };
+    ($value:expr) => {
+        $crate::utils::base64_encode(&$value)
+    };
+}
+
+#[macro_export]
+macro_rules! hex {
+    (decode: $value:expr, strict) => {
+        $crate::utils::hex_decode(&$value)
+    };
+    (decode: $value:expr) => {
+        $crate::utils::hex_decode(&$value).unwrap_or_else(|_| $value.to_string())
+    };
+    ($value:expr) => {
+        $crate::utils::hex_encode(&$value)
+    };
+}
+
+// Byte sizes: bytes!(n) is binary ("1.5 MiB"), bytes!(n, si) decimal;
+// bytes!(parse: "1.5MB") returns Result<u64, String>
+#[macro_export]
+macro_rules! bytes {
+    (parse: $value:expr) => {
+        $crate::utils::parse_bytes(&$value)
+    };
+    ($n:expr, si) => {
+        $crate::utils::format_bytes($n as u64, false)
+    };
+    ($n:expr) => {
+        $crate::utils::format_bytes($n as u64, true)
+    };
+}
+
+// Display-width aware padding, centering and truncation
+#[macro_export]
+macro_rules! str_pad {
+    ($value:expr, left: $width:expr, fill: $fill:expr) => {
+        $crate::utils::str_pad_left(&$value, $width, $fill)
+    };
+    ($value:expr, left: $width:expr) => {
+        $crate::utils::str_pad_left(&$value, $width, ' ')
+    };
+    ($value:expr, right: $width:expr, fill: $fill:expr) => {
+        $crate::utils::str_pad_right(&$value, $width, $fill)
+    };
+    ($value:expr, right: $width:expr) => {
+        $crate::utils::str_pad_right(&$value, $width, ' ')
+    };
+}
+
+#[macro_export]
+macro_rules! str_center {
+    ($value:expr, $width:expr) => {
+        $crate::utils::str_center(&$value, $width)
+    };
+}
+
+#[macro_export]
+macro_rules! str_truncate {
+    ($value:expr, $max:expr, ellipsis: $ellipsis:expr) => {
+        $crate::utils::str_truncate(&$value, $max, &$ellipsis)
+    };
+    ($value:expr, $max:expr) => {
+        $crate::utils::str_truncate(&$value, $max, "…")
+    };
+}
+
+// Multi-line text with $VAR expansion; `strip:` removes common indentation (<<-)
+#[macro_export]
+macro_rules! heredoc {
+    (strip: $text:expr) => {
+        $crate::context::expand_vars(&$crate::utils::dedent(&$text))
+    };
+    ($text:expr) => {
+        $crate::context::expand_vars(&$text)
+    };
+}
+
+// Build a JSON object string with proper escaping; values are anything
+// serde can serialize: json!({ "token" => token, "count" => 3 })
+#[macro_export]
+macro_rules! json {
+    ({ $($key:expr => $value:expr),* $(,)? }) => {{
+        let mut __obj = $crate::deps::serde_json::Map::new();
+        $(
+            __obj.insert(
+                $key.to_string(),
+                $crate::deps::serde_json::to_value(&$value).unwrap_or_default(),
+            );
+        )*
+        $crate::deps::serde_json::Value::Object(__obj).to_string()
+    }};
+}
+
+// capture_vars!(r"(?P<major>\d+)\.(?P<minor>\d+)", in: text, into: "V") sets V_major, V_minor
+#[macro_export]
+macro_rules! capture_vars {
+    ($pattern:expr, in: $text:expr, into: $prefix:expr) => {
+        $crate::utils::capture_vars(&$pattern, &$text, &$prefix)
+    };
+    ($pattern:expr, in: $text:expr) => { $crate::utils::capture_vars(&$pattern, &$text, "") };
+}
+
+#[macro_export]
+macro_rules! str_line {
+    ($ch:expr, $count:expr) => {
+        $crate::utils::str_line($ch, $count)
+    };
+}
+
+// --- sed family -------------------------------------------------------------------
+
+#[macro_export]
+macro_rules! sed_lines {
+    ($content:expr, $start:expr, $end:expr) => {
+        $crate::fs::sed_lines(&$content, $start, $end)
+    };
+}
+
+#[macro_export]
+macro_rules! sed_around {
+    ($content:expr, $pattern:expr, $context:expr) => {
+        $crate::fs::sed_around(&$content, &$pattern, $context)
+    };
+}
+
+#[macro_export]
+macro_rules! sed_insert {
+    ($content:expr, $sentinel:expr, $source:expr) => {
+        $crate::fs::sed_insert(&$content, &$sentinel, &$source)
+    };
+}
+
+#[macro_export]
+macro_rules! sed_template {
+    ($content:expr, $sentinel:expr, $source:expr) => {
+        $crate::fs::sed_template(&$content, &$sentinel, &$source)
+    };
+}
+
+// Like sed: first occurrence only, or every occurrence with `all` (s///g)
+#[macro_export]
+macro_rules! sed_replace {
+    ($source:expr, $from:expr, $to:expr, all) => {
+        $source.replace(&$from, &$to)
+    };
+    ($source:expr, $from:expr, $to:expr) => {
+        $source.replacen(&$from, &$to, 1)
+    };
+}
+
+#[macro_export]
+macro_rules! sed_lines_file {
+    ($path:expr, $start:expr, $end:expr) => {
+        $crate::fs::sed_lines_file(&$path, $start, $end)
+    };
+}
+
+#[macro_export]
+macro_rules! sed_around_file {
+    ($path:expr, $pattern:expr, $context:expr) => {
+        $crate::fs::sed_around_file(&$path, &$pattern, $context)
+    };
+}
+
+#[macro_export]
+macro_rules! sed_template_file {
+    ($path:expr, $content:expr, $sentinel:expr) => {
+        $crate::fs::sed_template_file(&$path, &$content, &$sentinel)
+    };
+}
+
+// Unified diff of two files, or of two strings
+#[macro_export]
+macro_rules! diff {
+    (files: $a:expr, $b:expr) => {
+        $crate::fs::diff_files(&$a, &$b)
+    };
+    ($old:expr, $new:expr) => {
+        $crate::fs::diff_text(&$old, &$new, "a", "b")
+    };
+}
+
+// Render a template file through expand_vars; `dry_run` returns the result instead
+#[macro_export]
+macro_rules! template {
+    (from: $from:expr, to: $to:expr, dry_run) => {
+        $crate::fs::render_template(&$from)
+    };
+    (from: $from:expr, to: $to:expr) => {
+        $crate::fs::template_file(&$from, &$to)
+    };
+    (from: $from:expr) => {
+        $crate::fs::render_template(&$from)
+    };
+}
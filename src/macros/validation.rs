@@ -0,0 +1,157 @@
+// Conditions (test!), fail-fast validation and non-exiting checks.
+
+#[macro_export]
+macro_rules! validate {
+    ($cond:expr, $($msg:tt)+) => {
+        if !$cond {
+            $crate::error!("Validation failed: {}", format!($($msg)+));
+            std::process::exit(1);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! require_file {
+    ($path:expr) => {
+        $crate::validate!($crate::fs::is_file(&$path), "File does not exist: {}", $path)
+    };
+}
+
+#[macro_export]
+macro_rules! require_dir {
+    ($path:expr) => {
+        $crate::validate!($crate::fs::is_dir(&$path), "Directory does not exist: {}", $path)
+    };
+}
+
+#[macro_export]
+macro_rules! require_command {
+    ($cmd:expr) => {
+        $crate::validate!($crate::os::is_command(&$cmd), "Command not found: {}", $cmd)
+    };
+}
+
+#[macro_export]
+macro_rules! require_var {
+    ($var:expr) => {
+        $crate::validate!($crate::context::has_var(&$var), "Required variable not set: {}", $var)
+    };
+}
+
+// Like require_*/validate! but reports and returns a bool instead of exiting
+#[macro_export]
+macro_rules! check {
+    (var: $var:expr) => {
+        $crate::check!($crate::context::has_var(&$var), "Required variable not set: {}", $var)
+    };
+    (file: $path:expr) => {
+        $crate::check!($crate::fs::is_file(&$path), "File does not exist: {}", $path)
+    };
+    (dir: $path:expr) => {
+        $crate::check!($crate::fs::is_dir(&$path), "Directory does not exist: {}", $path)
+    };
+    (cmd: $cmd:expr) => {
+        $crate::check!($crate::os::is_command(&$cmd), "Command not found: {}", $cmd)
+    };
+    ($cond:expr, $($msg:tt)+) => {{
+        let __ok: bool = $cond;
+        if !__ok {
+            $crate::error!("Check failed: {}", format!($($msg)+));
+        }
+        __ok
+    }};
+}
+
+// Aggregating checks over `var:`/`file:`/`dir:`/`cmd:` items, reporting one
+// error that lists everything missing. check_* return a bool; require_*
+// exit 1 like validate!.
+#[macro_export]
+macro_rules! check_all {
+    ($($kind:ident: $value:expr),+ $(,)?) => {{
+        let __missing: Vec<String> = [$($crate::__requirement!($kind: $value)),+]
+            .into_iter()
+            .filter(|(__ok, _)| !__ok)
+            .map(|(_, __what)| __what)
+            .collect();
+        if !__missing.is_empty() {
+            $crate::error!("Check failed: missing {}", __missing.join(", "));
+        }
+        __missing.is_empty()
+    }};
+}
+
+#[macro_export]
+macro_rules! check_any {
+    ($($kind:ident: $value:expr),+ $(,)?) => {{
+        let __items = [$($crate::__requirement!($kind: $value)),+];
+        let __ok = __items.iter().any(|(__ok, _)| *__ok);
+        if !__ok {
+            let __names: Vec<&str> = __items.iter().map(|(_, __what)| __what.as_str()).collect();
+            $crate::error!("Check failed: need at least one of {}", __names.join(", "));
+        }
+        __ok
+    }};
+}
+
+#[macro_export]
+macro_rules! require_all {
+    ($($items:tt)+) => {
+        if !$crate::check_all!($($items)+) {
+            std::process::exit(1);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! require_any {
+    ($($items:tt)+) => {
+        if !$crate::check_any!($($items)+) {
+            std::process::exit(1);
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __requirement {
+    (var: $var:expr) => {
+        ($crate::context::has_var(&$var), format!("variable {}", $var))
+    };
+    (file: $path:expr) => {
+        ($crate::fs::is_file(&$path), format!("file {}", $path))
+    };
+    (dir: $path:expr) => {
+        ($crate::fs::is_dir(&$path), format!("directory {}", $path))
+    };
+    (cmd: $cmd:expr) => {
+        ($crate::os::is_command(&$cmd), format!("command {}", $cmd))
+    };
+}
+
+// [[ ... ]] replacement
+#[macro_export]
+macro_rules! test {
+    (-f $path:expr) => { $crate::fs::is_file(&$path) };
+    (-d $path:expr) => { $crate::fs::is_dir(&$path) };
+    (-e $path:expr) => { $crate::fs::is_entity(&$path) };
+    (-L $path:expr) => { $crate::fs::is_link(&$path) };
+    (-r $path:expr) => { $crate::fs::is_readable(&$path) };
+    (-w $path:expr) => { $crate::fs::is_writable(&$path) };
+    (-x $path:expr) => { $crate::fs::is_executable(&$path) };
+    (-s $path:expr) => { $crate::fs::is_nonempty_file(&$path) };
+    (-n $val:expr) => { !$val.to_string().is_empty() };
+    (-z $val:expr) => { $val.to_string().is_empty() };
+    ($a:expr, ==, $b:expr) => { $a.to_string().as_str() == $b.to_string().as_str() };
+    ($a:expr, !=, $b:expr) => { $a.to_string().as_str() != $b.to_string().as_str() };
+    ($a:expr, =~, $b:expr) => { $crate::utils::str_matches(&$a.to_string(), &$b.to_string()) };
+    ($a:expr, -eq, $b:expr) => { $crate::utils::num_cmp(&$a.to_string(), &$b.to_string()) == Some(std::cmp::Ordering::Equal) };
+    ($a:expr, -ne, $b:expr) => { $crate::utils::num_cmp(&$a.to_string(), &$b.to_string()) != Some(std::cmp::Ordering::Equal) };
+    ($a:expr, -lt, $b:expr) => { $crate::utils::num_cmp(&$a.to_string(), &$b.to_string()) == Some(std::cmp::Ordering::Less) };
+    ($a:expr, -gt, $b:expr) => { $crate::utils::num_cmp(&$a.to_string(), &$b.to_string()) == Some(std::cmp::Ordering::Greater) };
+    ($a:expr, -le, $b:expr) => {
+        matches!($crate::utils::num_cmp(&$a.to_string(), &$b.to_string()), Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal))
+    };
+    ($a:expr, -ge, $b:expr) => {
+        matches!($crate::utils::num_cmp(&$a.to_string(), &$b.to_string()), Some(std::cmp::Ordering::Greater | std::cmp::Ordering::Equal))
+    };
+}
@@ -0,0 +1,33 @@
+// Terminal presentation: widths and layout.
+
+// Current terminal width; also refreshes the COLS context var
+#[macro_export]
+macro_rules! term_width {
+    () => {
+        $crate::visual::width_plugin::refresh_cols()
+    };
+}
+
+// On-screen width of a string, ignoring ANSI escapes
+#[macro_export]
+macro_rules! display_width {
+    ($text:expr) => {
+        $crate::visual::width_plugin::get_display_width(&$text)
+    };
+}
+
+// hl!(text, "red"): text wrapped in a color and reset; nests safely
+#[macro_export]
+macro_rules! hl {
+    ($text:expr, $color:expr) => {
+        $crate::utils::colorize(&$text, &$color)
+    };
+}
+
+// Switch color/glyph preset; false for an unknown theme name
+#[macro_export]
+macro_rules! theme {
+    ($name:expr) => {
+        $crate::context::set_theme(&$name)
+    };
+}
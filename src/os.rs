@@ -0,0 +1,1016 @@
+//! Operating system integration: commands, system info, processes, locks,
+//! archives, network helpers and background jobs.
+//!
+//! Most helpers shell out through `sh -c` the way a bash script would.
+
+use crate::context::expand_vars;
+use crate::error::{RsbError, RsbResult};
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default)]
+pub struct CmdResult {
+    pub status: i32,
+    pub output: String,
+    pub error: String,
+}
+
+impl CmdResult {
+    /// Ok(stdout) on success, otherwise a `RsbError::Command` naming `cmd`.
+    pub fn into_result(self, cmd: &str) -> RsbResult<String> {
+        if self.status == 0 {
+            Ok(self.output)
+        } else {
+            Err(RsbError::Command {
+                cmd: cmd.to_string(),
+                status: self.status,
+                stderr: self.error,
+            })
+        }
+    }
+}
+
+// --- Command execution -----------------------------------------------------
+
+pub fn run_cmd_with_status(cmd: &str) -> CmdResult {
+    match Command::new("sh").arg("-c").arg(cmd).output() {
+        Ok(output) => CmdResult {
+            status: output.status.code().unwrap_or(1),
+            output: String::from_utf8_lossy(&output.stdout).trim_end().to_string(),
+            error: String::from_utf8_lossy(&output.stderr).trim_end().to_string(),
+        },
+        Err(e) => CmdResult {
+            status: 127,
+            output: String::new(),
+            error: e.to_string(),
+        },
+    }
+}
+
+/// Quotes a value as one POSIX `sh` word: wrapped in single quotes, with
+/// embedded single quotes written as `'\''`. Nothing inside is expanded.
+pub fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Runs a command and returns stdout, exiting the process if it fails.
+pub fn run_cmd(cmd: &str) -> String {
+    match try_run_cmd(cmd) {
+        Ok(output) => output,
+        Err(e) => {
+            crate::error!("{}", e);
+            std::process::exit(e.status());
+        }
+    }
+}
+
+pub fn try_run_cmd(cmd: &str) -> RsbResult<String> {
+    run_cmd_with_status(cmd).into_result(cmd)
+}
+
+pub fn shell_exec(cmd: &str, silent: bool) -> String {
+    let result = run_cmd_with_status(cmd);
+    if result.status != 0 && !silent {
+        crate::error!("Command failed ({}): {}", result.status, cmd);
+    }
+    if result.status == 0 {
+        result.output
+    } else {
+        String::new()
+    }
+}
+
+/// Feeds `input` to the command's stdin and captures the result.
+pub fn run_cmd_with_input(cmd: &str, input: &str) -> CmdResult {
+    let child = Command::new("sh")
+        .arg("-c")
+        .arg(cmd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(c) => c,
+        Err(e) => {
+            return CmdResult {
+                status: 127,
+                output: String::new(),
+                error: e.to_string(),
+            }
+        }
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let input = input.to_string();
+        // Write on a thread so a large input can't deadlock against stdout.
+        std::thread::spawn(move || {
+            stdin.write_all(input.as_bytes()).ok();
+        });
+    }
+
+    match child.wait_with_output() {
+        Ok(output) => CmdResult {
+            status: output.status.code().unwrap_or(1),
+            output: String::from_utf8_lossy(&output.stdout).trim_end().to_string(),
+            error: String::from_utf8_lossy(&output.stderr).trim_end().to_string(),
+        },
+        Err(e) => CmdResult {
+            status: 1,
+            output: String::new(),
+            error: e.to_string(),
+        },
+    }
+}
+
+/// Runs `stages` as one `a | b | c` pipeline, each stage's stdout wired
+/// straight into the next one's stdin. Returns the last stage's stdout,
+/// every stage's stderr, and the rightmost non-zero status (`pipefail`).
+pub fn run_pipeline(stages: &[&str]) -> CmdResult {
+    let mut children: Vec<Child> = Vec::new();
+    let mut stderr_readers = Vec::new();
+    let mut upstream: Option<std::process::ChildStdout> = None;
+    for stage in stages {
+        let stdin = upstream.take().map_or_else(Stdio::null, Stdio::from);
+        let spawned = Command::new("sh")
+            .arg("-c")
+            .arg(stage)
+            .stdin(stdin)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                for mut started in children {
+                    started.kill().ok();
+                    started.wait().ok();
+                }
+                return CmdResult {
+                    status: 127,
+                    output: String::new(),
+                    error: e.to_string(),
+                };
+            }
+        };
+        upstream = child.stdout.take();
+        if let Some(mut stderr) = child.stderr.take() {
+            stderr_readers.push(std::thread::spawn(move || {
+                let mut text = String::new();
+                std::io::Read::read_to_string(&mut stderr, &mut text).ok();
+                text
+            }));
+        }
+        children.push(child);
+    }
+
+    let mut output = String::new();
+    if let Some(mut stdout) = upstream {
+        std::io::Read::read_to_string(&mut stdout, &mut output).ok();
+    }
+    let status = children
+        .iter_mut()
+        .map(|child| child.wait().ok().and_then(|s| s.code()).unwrap_or(1))
+        .fold(0, |last, code| if code != 0 { code } else { last });
+    let error: Vec<String> = stderr_readers
+        .into_iter()
+        .filter_map(|reader| reader.join().ok())
+        .map(|text| text.trim_end().to_string())
+        .filter(|text| !text.is_empty())
+        .collect();
+    CmdResult {
+        status,
+        output: output.trim_end().to_string(),
+        error: error.join("\n"),
+    }
+}
+
+/// Runs a command, handing each stdout line to `on_line` as it arrives.
+pub fn run_cmd_streaming<F: FnMut(&str)>(cmd: &str, mut on_line: F) -> i32 {
+    let child = Command::new("sh")
+        .arg("-c")
+        .arg(cmd)
+        .stdout(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(c) => c,
+        Err(_) => return 127,
+    };
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            on_line(&line);
+        }
+    }
+    child.wait().ok().and_then(|s| s.code()).unwrap_or(1)
+}
+
+/// Like `run_cmd`, showing a spinner with `message` on stderr while the
+/// command runs (nothing under QUIET_MODE). The spinner is cleared before
+/// any error is reported.
+pub fn run_cmd_spinner(cmd: &str, message: &str) -> String {
+    match try_run_cmd_spinner(cmd, message) {
+        Ok(output) => output,
+        Err(e) => {
+            crate::error!("{}", e);
+            std::process::exit(e.status());
+        }
+    }
+}
+
+pub fn try_run_cmd_spinner(cmd: &str, message: &str) -> RsbResult<String> {
+    let mut spinner = crate::visual::progress::Spinner::start(message);
+    let mut lines = Vec::new();
+    let status = run_cmd_streaming(cmd, |line| lines.push(line.to_string()));
+    spinner.stop();
+    CmdResult {
+        status,
+        output: lines.join("\n").trim_end().to_string(),
+        error: String::new(),
+    }
+    .into_result(cmd)
+}
+
+/// Replaces the current process with `program` (execvp), like shell
+/// `exec`. Only returns if the exec itself failed. On non-unix platforms
+/// the program is run to completion and we exit with its code.
+pub fn exec(program: &str, args: &[&str]) -> RsbError {
+    let program = expand_vars(program);
+    let mut command = Command::new(&program);
+    command.args(args);
+    let io_err = |source| RsbError::Io {
+        path: program.clone(),
+        source,
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        io_err(command.exec())
+    }
+    #[cfg(not(unix))]
+    {
+        match command.status() {
+            Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+            Err(e) => io_err(e),
+        }
+    }
+}
+
+/// Opens a file or URL with the platform's default application
+/// (`xdg-open`, `open` or `start`).
+pub fn open(target: &str) -> CmdResult {
+    let (program, args) = open_command(&expand_vars(target), std::env::consts::OS);
+    match Command::new(&program).args(&args).output() {
+        Ok(output) => CmdResult {
+            status: output.status.code().unwrap_or(1),
+            output: String::from_utf8_lossy(&output.stdout).trim_end().to_string(),
+            error: String::from_utf8_lossy(&output.stderr).trim_end().to_string(),
+        },
+        Err(e) => CmdResult {
+            status: 127,
+            output: String::new(),
+            error: format!("{}: {}", program, e),
+        },
+    }
+}
+
+/// The launcher program and arguments `open` uses on `os` (a
+/// `std::env::consts::OS` value).
+pub fn open_command(target: &str, os: &str) -> (String, Vec<String>) {
+    match os {
+        "macos" => ("open".to_string(), vec![target.to_string()]),
+        // `start` is a cmd builtin; its first quoted argument is the window title
+        "windows" => (
+            "cmd".to_string(),
+            vec!["/C".to_string(), "start".to_string(), String::new(), target.to_string()],
+        ),
+        _ => ("xdg-open".to_string(), vec![target.to_string()]),
+    }
+}
+
+pub fn is_command(cmd: &str) -> bool {
+    run_cmd_with_status(&format!("command -v {} >/dev/null 2>&1", sh_quote(cmd))).status == 0
+}
+
+/// A temp file preloaded with `content`, for commands that want a file
+/// argument (`diff <(...)` style). Removed by `cleanup_temp_files`.
+pub fn temp_with_content(content: &str) -> String {
+    let path = crate::fs::create_temp_file_path("random");
+    // Written even under dry-run: the file is an input, not a side effect
+    crate::fs::exit_on_write_error(crate::fs::try_write_file_opts(&path, content, &Default::default()));
+    path
+}
+
+// --- System information ------------------------------------------------------
+
+pub fn get_hostname() -> String {
+    let result = run_cmd_with_status("hostname");
+    if result.status == 0 && !result.output.is_empty() {
+        return result.output.split('.').next().unwrap_or("").to_string();
+    }
+    std::env::var("HOSTNAME").unwrap_or_else(|_| "localhost".to_string())
+}
+
+/// Fully qualified host name (canonical name from the resolver, then
+/// `hostname -f`), falling back to the short `get_hostname`.
+pub fn get_fqdn() -> String {
+    #[cfg(unix)]
+    if let Some(name) = canonical_hostname().filter(|n| n.contains('.')) {
+        return name;
+    }
+    let result = run_cmd_with_status("hostname -f 2>/dev/null");
+    if result.status == 0 && result.output.contains('.') {
+        return result.output.trim().to_string();
+    }
+    get_hostname()
+}
+
+#[cfg(unix)]
+fn canonical_hostname() -> Option<String> {
+    use std::ffi::{CStr, CString};
+    let mut buf = [0 as libc::c_char; 256];
+    if unsafe { libc::gethostname(buf.as_mut_ptr(), buf.len()) } != 0 {
+        return None;
+    }
+    let host = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_owned();
+    let host = CString::new(host.to_bytes()).ok()?;
+    let mut hints: libc::addrinfo = unsafe { std::mem::zeroed() };
+    hints.ai_flags = libc::AI_CANONNAME;
+    let mut info: *mut libc::addrinfo = std::ptr::null_mut();
+    if unsafe { libc::getaddrinfo(host.as_ptr(), std::ptr::null(), &hints, &mut info) } != 0 {
+        return None;
+    }
+    let canonical = unsafe {
+        let name = (*info).ai_canonname;
+        let canonical = (!name.is_null()).then(|| CStr::from_ptr(name).to_string_lossy().into_owned());
+        libc::freeaddrinfo(info);
+        canonical
+    };
+    canonical.filter(|n| !n.is_empty())
+}
+
+/// Addresses of this machine's network interfaces, IPv4 first, excluding
+/// loopback and IPv6 link-local. When nothing else is configured the
+/// loopback addresses are returned, so the list is never empty.
+pub fn get_local_ips() -> Vec<String> {
+    let mut addrs = interface_addrs();
+    addrs.sort_by_key(|ip| (ip.is_ipv6(), *ip));
+    addrs.dedup();
+    let is_link_local = |ip: &std::net::IpAddr| match ip {
+        std::net::IpAddr::V6(v6) => (v6.segments()[0] & 0xffc0) == 0xfe80,
+        std::net::IpAddr::V4(_) => false,
+    };
+    let routable: Vec<String> = addrs
+        .iter()
+        .filter(|ip| !ip.is_loopback() && !is_link_local(ip))
+        .map(|ip| ip.to_string())
+        .collect();
+    if !routable.is_empty() {
+        return routable;
+    }
+    let loopback: Vec<String> = addrs.iter().filter(|ip| ip.is_loopback()).map(|ip| ip.to_string()).collect();
+    if loopback.is_empty() {
+        vec!["127.0.0.1".to_string()]
+    } else {
+        loopback
+    }
+}
+
+#[cfg(unix)]
+fn interface_addrs() -> Vec<std::net::IpAddr> {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    let mut addrs = Vec::new();
+    let mut head: *mut libc::ifaddrs = std::ptr::null_mut();
+    if unsafe { libc::getifaddrs(&mut head) } != 0 {
+        return addrs;
+    }
+    let mut cursor = head;
+    while !cursor.is_null() {
+        unsafe {
+            let addr = (*cursor).ifa_addr;
+            if !addr.is_null() {
+                match i32::from((*addr).sa_family) {
+                    libc::AF_INET => {
+                        let v4 = &*(addr as *const libc::sockaddr_in);
+                        addrs.push(IpAddr::V4(Ipv4Addr::from(u32::from_be(v4.sin_addr.s_addr))));
+                    }
+                    libc::AF_INET6 => {
+                        let v6 = &*(addr as *const libc::sockaddr_in6);
+                        addrs.push(IpAddr::V6(Ipv6Addr::from(v6.sin6_addr.s6_addr)));
+                    }
+                    _ => {}
+                }
+            }
+            cursor = (*cursor).ifa_next;
+        }
+    }
+    unsafe { libc::freeifaddrs(head) };
+    addrs
+}
+
+#[cfg(not(unix))]
+fn interface_addrs() -> Vec<std::net::IpAddr> {
+    Vec::new()
+}
+
+pub fn get_username() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| run_cmd_with_status("whoami").output)
+}
+
+pub fn get_home_dir() -> String {
+    std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .unwrap_or_default()
+}
+
+pub fn get_current_dir() -> String {
+    std::env::current_dir()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+pub fn get_os() -> String {
+    std::env::consts::OS.to_string()
+}
+
+pub fn get_arch() -> String {
+    std::env::consts::ARCH.to_string()
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OsRelease {
+    pub id: String,
+    pub version_id: String,
+    pub pretty_name: String,
+}
+
+/// Parses `/etc/os-release` style `KEY=value` content.
+pub fn parse_os_release(content: &str) -> OsRelease {
+    let mut release = OsRelease::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches(['"', '\'']).to_string();
+        match key.trim() {
+            "ID" => release.id = value,
+            "VERSION_ID" => release.version_id = value,
+            "PRETTY_NAME" => release.pretty_name = value,
+            _ => {}
+        }
+    }
+    release
+}
+
+/// Distro id/version: `/etc/os-release` on Linux, `sw_vers` on macOS and
+/// the bare OS name elsewhere.
+pub fn get_os_release() -> OsRelease {
+    let os = get_os();
+    if os == "linux" {
+        for path in ["/etc/os-release", "/usr/lib/os-release"] {
+            if std::path::Path::new(path).is_file() {
+                return parse_os_release(&crate::fs::read_file(path));
+            }
+        }
+    }
+    if os == "macos" {
+        let version = run_cmd_with_status("sw_vers -productVersion").output;
+        return OsRelease {
+            id: "macos".to_string(),
+            pretty_name: format!("macOS {}", version).trim().to_string(),
+            version_id: version,
+        };
+    }
+    OsRelease {
+        id: os.clone(),
+        version_id: String::new(),
+        pretty_name: os,
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MemInfo {
+    pub total: u64,
+    pub available: u64,
+}
+
+/// Physical memory in bytes; zeroes where the platform isn't supported.
+pub fn get_memory() -> MemInfo {
+    if !sysinfo::IS_SUPPORTED_SYSTEM {
+        return MemInfo::default();
+    }
+    let mut sys = sysinfo::System::new();
+    sys.refresh_memory();
+    MemInfo {
+        total: sys.total_memory(),
+        available: sys.available_memory(),
+    }
+}
+
+/// 1, 5 and 15 minute load averages (all zero on Windows).
+pub fn get_load_average() -> (f64, f64, f64) {
+    let load = sysinfo::System::load_average();
+    (load.one, load.five, load.fifteen)
+}
+
+pub fn get_uptime() -> Duration {
+    Duration::from_secs(sysinfo::System::uptime())
+}
+
+/// Logical cores available to this process (1 if it can't be determined).
+/// The default worker count for `worker_pool`/`pforeach!`.
+pub fn cpu_count() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+// --- Processes -----------------------------------------------------------------
+
+/// Returns the first PID whose process name matches, or "".
+pub fn pid_of(name: &str) -> String {
+    pids_of(name).into_iter().next().unwrap_or_default()
+}
+
+/// Every PID whose process name matches the pattern, lowest first.
+/// Uses the native process table, falling back to `pgrep`.
+pub fn pids_of(name: &str) -> Vec<String> {
+    if sysinfo::IS_SUPPORTED_SYSTEM {
+        let Ok(re) = regex::Regex::new(name) else {
+            return Vec::new();
+        };
+        let sys = process_table(sysinfo::ProcessesToUpdate::All);
+        let mut pids: Vec<u32> = sys
+            .processes()
+            .values()
+            .filter(|p| p.thread_kind().is_none())
+            .filter(|p| re.is_match(&p.name().to_string_lossy()))
+            .map(|p| p.pid().as_u32())
+            .collect();
+        pids.sort_unstable();
+        return pids.iter().map(u32::to_string).collect();
+    }
+
+    let result = run_cmd_with_status(&format!("pgrep {}", sh_quote(name)));
+    result
+        .output
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+fn process_table(which: sysinfo::ProcessesToUpdate) -> sysinfo::System {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes_specifics(which, true, sysinfo::ProcessRefreshKind::nothing());
+    sys
+}
+
+fn native_signal(signal: &str) -> Option<sysinfo::Signal> {
+    use sysinfo::Signal;
+    let name = signal.trim_start_matches("SIG").to_uppercase();
+    Some(match name.as_str() {
+        "HUP" | "1" => Signal::Hangup,
+        "INT" | "2" => Signal::Interrupt,
+        "QUIT" | "3" => Signal::Quit,
+        "KILL" | "9" => Signal::Kill,
+        "USR1" | "10" => Signal::User1,
+        "USR2" | "12" => Signal::User2,
+        "TERM" | "15" => Signal::Term,
+        "CONT" => Signal::Continue,
+        "STOP" => Signal::Stop,
+        _ => return None,
+    })
+}
+
+pub fn process_exists(name: &str) -> bool {
+    !pid_of(name).is_empty()
+}
+
+pub fn process_exists_by_pid(pid: &str) -> bool {
+    let Ok(id) = pid.trim().parse::<u32>() else {
+        return false;
+    };
+    if sysinfo::IS_SUPPORTED_SYSTEM {
+        let pid = sysinfo::Pid::from_u32(id);
+        return process_table(sysinfo::ProcessesToUpdate::Some(&[pid]))
+            .process(pid)
+            .is_some();
+    }
+    run_cmd_with_status(&format!("ps -p {} >/dev/null 2>&1", id)).status == 0
+}
+
+/// Signals a PID natively when possible, otherwise through `kill`.
+pub fn kill_pid(pid: &str, signal: Option<&str>) -> CmdResult {
+    if sysinfo::IS_SUPPORTED_SYSTEM {
+        let sig = native_signal(signal.unwrap_or("TERM"));
+        if let (Ok(id), Some(sig)) = (pid.trim().parse::<u32>(), sig) {
+            let pid = sysinfo::Pid::from_u32(id);
+            let sys = process_table(sysinfo::ProcessesToUpdate::Some(&[pid]));
+            return match sys.process(pid).map(|p| p.kill_with(sig)) {
+                Some(Some(true)) => CmdResult::default(),
+                Some(_) => CmdResult {
+                    status: 1,
+                    output: String::new(),
+                    error: format!("failed to signal {}", id),
+                },
+                None => CmdResult {
+                    status: 1,
+                    output: String::new(),
+                    error: format!("no such process: {}", id),
+                },
+            };
+        }
+    }
+    match signal {
+        Some(sig) => run_cmd_with_status(&format!("kill -{} {}", sig, pid)),
+        None => run_cmd_with_status(&format!("kill {}", pid)),
+    }
+}
+
+/// Signal number for a name ("HUP", "SIGterm") or a number ("9", "0").
+#[cfg(unix)]
+pub fn signal_number(name: &str) -> Option<i32> {
+    if let Ok(n) = name.trim().parse::<i32>() {
+        return (0..=64).contains(&n).then_some(n);
+    }
+    let upper = name.trim().to_uppercase();
+    Some(match upper.strip_prefix("SIG").unwrap_or(&upper) {
+        "HUP" => libc::SIGHUP,
+        "INT" => libc::SIGINT,
+        "QUIT" => libc::SIGQUIT,
+        "ABRT" => libc::SIGABRT,
+        "KILL" => libc::SIGKILL,
+        "USR1" => libc::SIGUSR1,
+        "USR2" => libc::SIGUSR2,
+        "PIPE" => libc::SIGPIPE,
+        "ALRM" => libc::SIGALRM,
+        "TERM" => libc::SIGTERM,
+        "CHLD" => libc::SIGCHLD,
+        "CONT" => libc::SIGCONT,
+        "STOP" => libc::SIGSTOP,
+        "TSTP" => libc::SIGTSTP,
+        "WINCH" => libc::SIGWINCH,
+        _ => return None,
+    })
+}
+
+/// Sends `signal` (name or number) to `pid` with `kill(2)`, no `kill`
+/// binary needed. Signal 0 only checks the process exists and may be signalled.
+#[cfg(unix)]
+pub fn send_signal(pid: i32, signal: &str) -> RsbResult<()> {
+    let Some(sig) = signal_number(signal) else {
+        return Err(RsbError::Signal {
+            pid,
+            reason: format!("unknown signal '{}'", signal),
+        });
+    };
+    if unsafe { libc::kill(pid, sig) } == 0 {
+        Ok(())
+    } else {
+        Err(RsbError::Signal {
+            pid,
+            reason: std::io::Error::last_os_error().to_string(),
+        })
+    }
+}
+
+pub fn kill_process(name: &str, signal: Option<&str>) -> CmdResult {
+    match signal {
+        Some(sig) => run_cmd_with_status(&format!("pkill -{} {}", sig, sh_quote(name))),
+        None => run_cmd_with_status(&format!("pkill {}", sh_quote(name))),
+    }
+}
+
+// --- Locks -------------------------------------------------------------------------
+
+/// Creates a lock file holding our PID, exiting if the lock is already held.
+pub fn create_lock(path: &str) {
+    if let Err(e) = try_create_lock(path) {
+        crate::error!("{}", e);
+        std::process::exit(e.status());
+    }
+}
+
+pub fn try_create_lock(path: &str) -> RsbResult<()> {
+    let path = expand_vars(path);
+    if std::path::Path::new(&path).exists() {
+        let holder = crate::fs::read_file(&path).trim().to_string();
+        return Err(RsbError::LockHeld { path, holder });
+    }
+    std::fs::write(&path, std::process::id().to_string())
+        .map_err(|source| RsbError::Io { path, source })
+}
+
+pub fn release_lock(path: &str) {
+    std::fs::remove_file(expand_vars(path)).ok();
+}
+
+// --- Archives ----------------------------------------------------------------------
+
+fn quoted_paths(paths: &[&str]) -> String {
+    paths
+        .iter()
+        .map(|p| sh_quote(&expand_vars(p)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn create_tar(archive: &str, paths: &[&str]) -> CmdResult {
+    run_cmd_with_status(&format!(
+        "tar -cf {} {}",
+        sh_quote(&expand_vars(archive)),
+        quoted_paths(paths)
+    ))
+}
+
+pub fn create_tar_gz(archive: &str, paths: &[&str]) -> CmdResult {
+    run_cmd_with_status(&format!(
+        "tar -czf {} {}",
+        sh_quote(&expand_vars(archive)),
+        quoted_paths(paths)
+    ))
+}
+
+pub fn create_zip(archive: &str, paths: &[&str]) -> CmdResult {
+    run_cmd_with_status(&format!(
+        "zip -rq {} {}",
+        sh_quote(&expand_vars(archive)),
+        quoted_paths(paths)
+    ))
+}
+
+pub fn extract_tar(archive: &str, dest: Option<&str>) -> CmdResult {
+    let archive = expand_vars(archive);
+    let flags = if archive.ends_with(".gz") || archive.ends_with(".tgz") {
+        "-xzf"
+    } else {
+        "-xf"
+    };
+    match dest {
+        Some(d) => {
+            std::fs::create_dir_all(expand_vars(d)).ok();
+            run_cmd_with_status(&format!(
+                "tar {} {} -C {}",
+                flags,
+                sh_quote(&archive),
+                sh_quote(&expand_vars(d))
+            ))
+        }
+        None => run_cmd_with_status(&format!("tar {} {}", flags, sh_quote(&archive))),
+    }
+}
+
+pub fn extract_zip(archive: &str, dest: Option<&str>) -> CmdResult {
+    let archive = expand_vars(archive);
+    match dest {
+        Some(d) => run_cmd_with_status(&format!(
+            "unzip -oq {} -d {}",
+            sh_quote(&archive),
+            sh_quote(&expand_vars(d))
+        )),
+        None => run_cmd_with_status(&format!("unzip -oq {}", sh_quote(&archive))),
+    }
+}
+
+// Non-exiting archive operations; Ok holds the command's stdout
+pub fn try_create_tar(archive: &str, paths: &[&str]) -> RsbResult<String> {
+    create_tar(archive, paths).into_result(&format!("tar -cf {}", archive))
+}
+
+pub fn try_create_tar_gz(archive: &str, paths: &[&str]) -> RsbResult<String> {
+    create_tar_gz(archive, paths).into_result(&format!("tar -czf {}", archive))
+}
+
+pub fn try_create_zip(archive: &str, paths: &[&str]) -> RsbResult<String> {
+    create_zip(archive, paths).into_result(&format!("zip {}", archive))
+}
+
+pub fn try_extract_tar(archive: &str, dest: Option<&str>) -> RsbResult<String> {
+    extract_tar(archive, dest).into_result(&format!("tar -xf {}", archive))
+}
+
+pub fn try_extract_zip(archive: &str, dest: Option<&str>) -> RsbResult<String> {
+    extract_zip(archive, dest).into_result(&format!("unzip {}", archive))
+}
+
+pub fn list_tar(archive: &str) -> CmdResult {
+    run_cmd_with_status(&format!("tar -tf {}", sh_quote(&expand_vars(archive))))
+}
+
+pub fn list_zip(archive: &str) -> CmdResult {
+    run_cmd_with_status(&format!("unzip -Z1 {}", sh_quote(&expand_vars(archive))))
+}
+
+// --- Network & JSON -------------------------------------------------------------------
+
+/// Performs an HTTP request through curl; `status` is curl's exit code.
+pub fn http_request(method: &str, url: &str, data: Option<&str>, options: &str) -> CmdResult {
+    let mut cmd = format!("curl -s -L -X {} {}", method, options);
+    if let Some(d) = data {
+        cmd.push_str(&format!(" --data {}", sh_quote(d)));
+    }
+    cmd.push_str(&format!(" {}", sh_quote(url)));
+    run_cmd_with_status(&cmd)
+}
+
+/// An HTTP response as seen by curl. `status` is the HTTP code, or 0 when
+/// no response arrived (DNS, refused connection, timeout).
+#[derive(Debug, Clone, Default)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..400).contains(&self.status)
+    }
+
+    // Worth another attempt: no response or a 5xx
+    pub fn is_retryable(&self) -> bool {
+        self.status == 0 || self.status >= 500
+    }
+}
+
+/// Like `http_request`, but also reports the HTTP status code.
+pub fn http_request_status(method: &str, url: &str, data: Option<&str>, options: &str) -> HttpResponse {
+    let options = format!("{} -w '\\n%{{http_code}}'", options);
+    let result = http_request(method, url, data, options.trim_start());
+    let (body, code) = result.output.rsplit_once('\n').unwrap_or(("", &result.output));
+    HttpResponse {
+        status: code.trim().parse().unwrap_or(0),
+        body: body.to_string(),
+    }
+}
+
+/// GETs `url` up to `attempts` times, retrying only on connection errors and
+/// 5xx with `delay_ms` between tries. A 4xx fails straight away.
+pub fn retry_http(url: &str, attempts: usize, delay_ms: u64) -> RsbResult<String> {
+    let mut response = HttpResponse::default();
+    for attempt in 1..=attempts.max(1) {
+        response = http_request_status("GET", url, None, "");
+        if response.is_success() {
+            return Ok(response.body);
+        }
+        if !response.is_retryable() {
+            break;
+        }
+        if attempt < attempts {
+            crate::warn!("Request to {} failed ({}), retrying...", url, response.status);
+            crate::time::sleep_ms(delay_ms);
+        }
+    }
+    Err(RsbError::Http {
+        url: url.to_string(),
+        status: response.status,
+    })
+}
+
+pub fn http_get(url: &str) -> String {
+    http_request("GET", url, None, "").output
+}
+
+pub fn http_post(url: &str, data: &str) -> String {
+    http_request("POST", url, Some(data), "").output
+}
+
+pub fn curl(url: &str, options: &str) -> String {
+    http_request("GET", url, None, options).output
+}
+
+/// Extracts a value with `jq -r` (requires jq on PATH).
+pub fn json_get(json: &str, path: &str) -> String {
+    let result = run_cmd_with_input(&format!("jq -r {}", sh_quote(path)), json);
+    if result.status == 0 {
+        result.output
+    } else {
+        String::new()
+    }
+}
+
+/// Flattens a JSON document into sorted `a.b[0].c=value` lines for
+/// grepping. Strings are written unquoted; empty objects/arrays as `{}`/`[]`.
+/// Invalid JSON warns and yields nothing.
+pub fn json_flatten(json: &str) -> Vec<String> {
+    match serde_json::from_str::<serde_json::Value>(json) {
+        Ok(value) => {
+            let mut lines = Vec::new();
+            flatten_value("", &value, &mut lines);
+            lines
+        }
+        Err(e) => {
+            crate::warn!("Invalid JSON: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn flatten_value(path: &str, value: &serde_json::Value, lines: &mut Vec<String>) {
+    use serde_json::Value;
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                flatten_value(&child_path, child, lines);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (i, child) in items.iter().enumerate() {
+                flatten_value(&format!("{}[{}]", path, i), child, lines);
+            }
+        }
+        Value::String(text) => lines.push(format!("{}={}", path, text)),
+        other => lines.push(format!("{}={}", path, other)),
+    }
+}
+
+pub fn json_get_file(file: &str, path: &str) -> String {
+    json_get(&crate::fs::read_file(file), path)
+}
+
+// --- Background jobs ----------------------------------------------------------------
+
+lazy_static! {
+    static ref JOBS: Mutex<HashMap<u32, (String, Child)>> = Mutex::new(HashMap::new());
+}
+static NEXT_JOB_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Starts a command in the background and returns its job id.
+pub fn start_job(cmd: &str) -> u32 {
+    let id = NEXT_JOB_ID.fetch_add(1, Ordering::SeqCst);
+    match Command::new("sh").arg("-c").arg(cmd).spawn() {
+        Ok(child) => {
+            JOBS.lock().unwrap().insert(id, (cmd.to_string(), child));
+        }
+        Err(e) => crate::error!("Failed to start job '{}': {}", cmd, e),
+    }
+    id
+}
+
+/// Waits for a job, optionally bounded by `timeout_secs`. Returns the exit
+/// code, or None if the job is unknown or the timeout elapsed (the job is
+/// killed in that case).
+pub fn wait_on_job(id: u32, timeout_secs: Option<u64>) -> Option<i32> {
+    let (cmd, mut child) = JOBS.lock().unwrap().remove(&id)?;
+
+    let Some(secs) = timeout_secs else {
+        return child.wait().ok().and_then(|s| s.code());
+    };
+
+    let pid = child.id();
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        tx.send(child.wait().ok().and_then(|s| s.code())).ok();
+    });
+    match rx.recv_timeout(Duration::from_secs(secs)) {
+        Ok(code) => code,
+        Err(_) => {
+            crate::warn!("Job {} timed out after {}s: {}", id, secs, cmd);
+            kill_pid(&pid.to_string(), Some("KILL"));
+            None
+        }
+    }
+}
+
+pub fn list_jobs() -> Vec<(u32, String)> {
+    let mut jobs: Vec<(u32, String)> = JOBS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(id, (cmd, _))| (*id, cmd.clone()))
+        .collect();
+    jobs.sort();
+    jobs
+}
+
+/// Runs `work` over `items` on at most `threads` worker threads (0 means
+/// `cpu_count()`) and returns the results in input order. Workers pull the
+/// next item as they free up, so uneven items still balance across the pool.
+pub fn worker_pool<T, F>(items: Vec<String>, threads: usize, work: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(String) -> T + Sync,
+{
+    let total = items.len();
+    let threads = if threads == 0 { cpu_count() } else { threads };
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let results = Mutex::new(Vec::with_capacity(total));
+    std::thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, total.max(1)) {
+            scope.spawn(|| loop {
+                let Some((index, item)) = queue.lock().unwrap().next() else {
+                    break;
+                };
+                let value = work(item);
+                results.lock().unwrap().push((index, value));
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, value)| value).collect()
+}
@@ -0,0 +1,16 @@
+//! `use rsb::prelude::*;` brings in the whole RSB vocabulary: functions,
+//! the `Args`/`Stream` types and every macro.
+
+pub use crate::args::{apply_options, Args};
+pub use crate::context::*;
+pub use crate::error::{RsbError, RsbResult};
+pub use crate::fs::*;
+pub use crate::os::*;
+pub use crate::random::*;
+pub use crate::streamable::{Streamable, *};
+pub use crate::streams::Stream;
+pub use crate::time::*;
+pub use crate::utils::*;
+
+// Macros are exported at the crate root
+pub use crate::*;
@@ -0,0 +1,158 @@
+//! Random values and generated word dictionaries.
+
+use crate::utils::{get_array, set_array};
+use rand::distr::{Alphanumeric, SampleString};
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::Rng;
+
+pub fn get_rand_alnum(n: usize) -> String {
+    Alphanumeric.sample_string(&mut rand::rng(), n)
+}
+
+pub fn get_rand_alpha(n: usize) -> String {
+    const ALPHA: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut rng = rand::rng();
+    (0..n)
+        .map(|_| ALPHA[rng.random_range(0..ALPHA.len())] as char)
+        .collect()
+}
+
+pub fn get_rand_hex(n: usize) -> String {
+    const HEX: &[u8] = b"0123456789abcdef";
+    let mut rng = rand::rng();
+    (0..n)
+        .map(|_| HEX[rng.random_range(0..HEX.len())] as char)
+        .collect()
+}
+
+// Printable ASCII without whitespace
+pub fn get_rand_string(n: usize) -> String {
+    let mut rng = rand::rng();
+    (0..n)
+        .map(|_| rng.random_range(b'!'..=b'~') as char)
+        .collect()
+}
+
+pub fn get_rand_uuid() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+// Inclusive range
+pub fn get_rand_range(min: usize, max: usize) -> usize {
+    if min >= max {
+        return min;
+    }
+    rand::rng().random_range(min..=max)
+}
+
+pub fn get_rand_from_slice(items: &[String]) -> Option<String> {
+    items.choose(&mut rand::rng()).cloned()
+}
+
+pub fn shuffle_vec(items: &mut [String]) {
+    items.shuffle(&mut rand::rng());
+}
+
+// Shuffles a context array in place
+pub fn shuffle_array(key: &str) {
+    let mut items = get_array(key);
+    shuffle_vec(&mut items);
+    let refs: Vec<&str> = items.iter().map(String::as_str).collect();
+    set_array(key, &refs);
+}
+
+// --- Dictionaries -------------------------------------------------------------------
+
+// Built-in wordlist for `gen_dict!(words, ...)` when none is loaded
+const WORDS: &[&str] = &[
+    "amber", "anchor", "apple", "arrow", "autumn", "badge", "basket", "beacon", "birch", "blade",
+    "bloom", "bridge", "butter", "cabin", "candle", "canyon", "castle", "cedar", "cherry", "cloud",
+    "comet", "copper", "coral", "cotton", "crane", "dawn", "delta", "desert", "ember", "falcon",
+    "feather", "fern", "forest", "frost", "garden", "glacier", "harbor", "hazel", "island", "ivory",
+    "jasper", "lantern", "lemon", "lotus", "maple", "meadow", "mirror", "moss", "nectar", "ocean",
+    "orbit", "pebble", "pepper", "pine", "planet", "quartz", "raven", "river", "saddle", "silver",
+    "spark", "stone", "thunder", "timber", "tulip", "velvet", "willow", "winter",
+];
+
+const CONSONANTS: &[u8] = b"bcdfghjklmnprstvz";
+const VOWELS: &[u8] = b"aeiou";
+
+/// A pronounceable pseudo-word of `syllables` consonant-vowel pairs, with
+/// an occasional closing consonant.
+pub fn get_rand_syllables(syllables: usize) -> String {
+    let mut rng = rand::rng();
+    let mut word = String::new();
+    for _ in 0..syllables {
+        word.push(CONSONANTS[rng.random_range(0..CONSONANTS.len())] as char);
+        word.push(VOWELS[rng.random_range(0..VOWELS.len())] as char);
+    }
+    if rng.random_bool(0.3) {
+        word.push(CONSONANTS[rng.random_range(0..CONSONANTS.len())] as char);
+    }
+    word
+}
+
+/// Loads a whitespace-separated wordlist file.
+pub fn load_dict(path: &str) -> Vec<String> {
+    crate::fs::read_file(path)
+        .split_whitespace()
+        .map(|w| w.to_string())
+        .collect()
+}
+
+/// Generates `count` random words of the given kind (alnum, alpha, hex,
+/// string) with lengths between 4 and 10 and stores them as a context array.
+/// `words` draws from the built-in wordlist and `syllable` makes
+/// pronounceable pseudo-words of 2-4 syllables.
+pub fn gen_dict(kind: &str, count: usize, into: &str) {
+    if kind == "words" {
+        let builtin: Vec<String> = WORDS.iter().map(|w| w.to_string()).collect();
+        return pick_words(&builtin, count, into);
+    }
+    let words: Vec<String> = (0..count)
+        .map(|_| {
+            let len = get_rand_range(4, 10);
+            match kind {
+                "alpha" => get_rand_alpha(len),
+                "hex" => get_rand_hex(len),
+                "string" => get_rand_string(len),
+                "syllable" => get_rand_syllables(get_rand_range(2, 4)),
+                _ => get_rand_alnum(len),
+            }
+        })
+        .collect();
+    let refs: Vec<&str> = words.iter().map(String::as_str).collect();
+    set_array(into, &refs);
+}
+
+/// Like `gen_dict("words", ..)` but drawing from a wordlist file (see `load_dict`).
+pub fn gen_dict_from(path: &str, count: usize, into: &str) {
+    pick_words(&load_dict(path), count, into);
+}
+
+fn pick_words(list: &[String], count: usize, into: &str) {
+    let words: Vec<String> = (0..count).filter_map(|_| get_rand_from_slice(list)).collect();
+    let refs: Vec<&str> = words.iter().map(String::as_str).collect();
+    set_array(into, &refs);
+}
+
+/// Picks `n` words from a context array (with replacement) joined by `delim`.
+pub fn rand_dict(key: &str, n: usize, delim: &str) -> String {
+    let items = get_array(key);
+    if items.is_empty() {
+        return String::new();
+    }
+    (0..n)
+        .filter_map(|_| get_rand_from_slice(&items))
+        .collect::<Vec<_>>()
+        .join(delim)
+}
+
+/// Like `rand_dict` but without replacement: no word is picked twice. If `n`
+/// exceeds the array size the whole array is returned, shuffled.
+pub fn rand_dict_unique(key: &str, n: usize, delim: &str) -> String {
+    let mut items = get_array(key);
+    shuffle_vec(&mut items);
+    items.truncate(n);
+    items.join(delim)
+}
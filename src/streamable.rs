@@ -0,0 +1,92 @@
+//! Streamable transforms: small, named string functions that can be applied
+//! to a single value or to every line of a `Stream`.
+
+pub trait Streamable {
+    type Args;
+    fn stream_apply(value: &str, args: Self::Args) -> String;
+}
+
+pub struct ToUpper;
+impl Streamable for ToUpper {
+    type Args = ();
+    fn stream_apply(value: &str, _: ()) -> String {
+        value.to_uppercase()
+    }
+}
+
+pub struct ToLower;
+impl Streamable for ToLower {
+    type Args = ();
+    fn stream_apply(value: &str, _: ()) -> String {
+        value.to_lowercase()
+    }
+}
+
+pub struct Trim;
+impl Streamable for Trim {
+    type Args = ();
+    fn stream_apply(value: &str, _: ()) -> String {
+        value.trim().to_string()
+    }
+}
+
+pub struct Reverse;
+impl Streamable for Reverse {
+    type Args = ();
+    fn stream_apply(value: &str, _: ()) -> String {
+        value.chars().rev().collect()
+    }
+}
+
+pub struct Replace;
+impl Streamable for Replace {
+    type Args = (String, String);
+    fn stream_apply(value: &str, (from, to): (String, String)) -> String {
+        value.replace(&from, &to)
+    }
+}
+
+pub struct Base64Encode;
+impl Streamable for Base64Encode {
+    type Args = ();
+    fn stream_apply(value: &str, _: ()) -> String {
+        crate::utils::base64_encode(value)
+    }
+}
+
+// Invalid input passes through unchanged
+pub struct Base64Decode;
+impl Streamable for Base64Decode {
+    type Args = ();
+    fn stream_apply(value: &str, _: ()) -> String {
+        crate::utils::base64_decode(value).unwrap_or_else(|_| value.to_string())
+    }
+}
+
+pub struct UrlEncode;
+impl Streamable for UrlEncode {
+    type Args = ();
+    fn stream_apply(value: &str, _: ()) -> String {
+        urlencoding::encode(value).to_string()
+    }
+}
+
+pub struct UrlDecode;
+impl Streamable for UrlDecode {
+    type Args = ();
+    fn stream_apply(value: &str, _: ()) -> String {
+        urlencoding::decode(value)
+            .map(|s| s.to_string())
+            .unwrap_or_else(|_| value.to_string())
+    }
+}
+
+impl crate::streams::Stream {
+    /// Applies a streamable transform to every line.
+    pub fn apply<T: Streamable>(self, args: T::Args) -> Self
+    where
+        T::Args: Clone,
+    {
+        self.map(|line| T::stream_apply(line, args.clone()))
+    }
+}
@@ -0,0 +1,915 @@
+//! Line-oriented streams: chainable, bash-pipe style text processing.
+
+use crate::context::{expand_vars, get_var};
+use crate::fs::{append_file, read_file, write_file};
+use crate::os::run_cmd_with_input;
+use std::collections::HashMap;
+
+// Stream operations for chainable processing
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stream {
+    lines: Vec<String>,
+}
+
+impl Stream {
+    pub fn new() -> Self {
+        Stream { lines: Vec::new() }
+    }
+
+    // --- Sources -----------------------------------------------------------
+
+    pub fn from_string(content: &str) -> Self {
+        Stream {
+            lines: content.lines().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    pub fn from_vec(lines: &[String]) -> Self {
+        Stream {
+            lines: lines.to_vec(),
+        }
+    }
+
+    pub fn from_delimited_string(content: &str, delim: &str) -> Self {
+        Stream {
+            lines: content.split(delim).map(|s| s.to_string()).collect(),
+        }
+    }
+
+    pub fn from_file(path: &str) -> Self {
+        Stream::from_string(&read_file(path))
+    }
+
+    // A binary-safe view of any file: its bytes as wrapped base64 lines
+    pub fn from_file_base64(path: &str) -> Self {
+        Stream::from_string(&crate::fs::encode_file_base64(path))
+    }
+
+    // Glob patterns expand to their matches in sorted order, like `cat *.log`
+    pub fn from_files(paths: &[&str]) -> Self {
+        let mut lines = Vec::new();
+        for pattern in paths {
+            for path in crate::fs::expand_glob(pattern) {
+                lines.extend(Stream::from_file(&path).lines);
+            }
+        }
+        Stream { lines }
+    }
+
+    pub fn from_cmd(cmd: &str) -> Self {
+        Stream::from_string(&crate::os::run_cmd(&expand_vars(cmd)))
+    }
+
+    // Any byte source (socket, decompressor...), read to the end; invalid
+    // UTF-8 is replaced rather than rejected
+    pub fn from_reader<R: std::io::Read>(mut reader: R) -> Self {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).ok();
+        Stream::from_string(&String::from_utf8_lossy(&bytes))
+    }
+
+    // Line by line, without holding the raw input in memory; stops at the
+    // first read error
+    pub fn from_buf_reader<R: std::io::BufRead>(reader: R) -> Self {
+        Stream {
+            lines: reader.lines().map_while(Result::ok).collect(),
+        }
+    }
+
+    pub fn from_var(name: &str) -> Self {
+        Stream::from_string(&get_var(name))
+    }
+
+    // One compact JSON document per line (JSONL)
+    pub fn from_json_lines(values: &[serde_json::Value]) -> Self {
+        Stream {
+            lines: values.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+
+    // Current text contents of the system clipboard
+    #[cfg(feature = "clipboard")]
+    pub fn from_clipboard() -> crate::error::RsbResult<Self> {
+        let text = clipboard()?
+            .get_text()
+            .map_err(|e| crate::error::RsbError::Clipboard(e.to_string()))?;
+        Ok(Stream::from_string(&text))
+    }
+
+    // --- Operators -----------------------------------------------------------
+
+    pub fn grep(mut self, pattern: &str) -> Self {
+        self.lines.retain(|line| line.contains(pattern));
+        self
+    }
+
+    pub fn grep_v(mut self, pattern: &str) -> Self {
+        self.lines.retain(|line| !line.contains(pattern));
+        self
+    }
+
+    // Regex variant of grep
+    pub fn grep_re(mut self, pattern: &str) -> Self {
+        self.lines
+            .retain(|line| crate::utils::str_matches(line, pattern));
+        self
+    }
+
+    /// Replaces every occurrence on each line, like `sed 's/from/to/g'`.
+    pub fn sed(mut self, from: &str, to: &str) -> Self {
+        self.lines = self.lines.iter().map(|line| line.replace(from, to)).collect();
+        self
+    }
+
+    /// Replaces only the first occurrence on each line, like `sed 's/from/to/'`.
+    pub fn sed_first(mut self, from: &str, to: &str) -> Self {
+        self.lines = self.lines.iter().map(|line| line.replacen(from, to, 1)).collect();
+        self
+    }
+
+    pub fn sed_re(mut self, pattern: &str, to: &str) -> Self {
+        if let Ok(re) = regex::Regex::new(pattern) {
+            self.lines = self
+                .lines
+                .iter()
+                .map(|line| re.replace_all(line, to).to_string())
+                .collect();
+        }
+        self
+    }
+
+    // Several literal substitutions, applied in order to each line in one pass
+    pub fn sed_multi(mut self, pairs: &[(&str, &str)]) -> Self {
+        for line in self.lines.iter_mut() {
+            for (from, to) in pairs {
+                *line = line.replace(from, to);
+            }
+        }
+        self
+    }
+
+    // Regex variant of sed_multi; invalid patterns are skipped
+    pub fn sed_re_multi(mut self, pairs: &[(&str, &str)]) -> Self {
+        let compiled: Vec<(regex::Regex, &str)> = pairs
+            .iter()
+            .filter_map(|(pattern, to)| regex::Regex::new(pattern).ok().map(|re| (re, *to)))
+            .collect();
+        for line in self.lines.iter_mut() {
+            for (re, to) in &compiled {
+                *line = re.replace_all(line, *to).to_string();
+            }
+        }
+        self
+    }
+
+    // Keep lines start..=end (1-indexed)
+    pub fn sed_lines(self, start: usize, end: usize) -> Self {
+        Stream::from_string(&crate::fs::sed_lines(&self.to_string(), start, end))
+    }
+
+    // Replace lines start..=end (1-indexed) with `replacement`, which may span
+    // several lines. The end is clamped; a range starting past the end, or an
+    // empty/zero range, leaves the stream unchanged.
+    pub fn replace_lines(mut self, start: usize, end: usize, replacement: &str) -> Self {
+        if start == 0 || start > end || start > self.lines.len() {
+            return self;
+        }
+        let end = end.min(self.lines.len());
+        self.lines
+            .splice(start - 1..end, replacement.lines().map(String::from));
+        self
+    }
+
+    // sed 'START,ENDd': drops lines start..=end (1-indexed, end clamped)
+    pub fn delete_range(self, start: usize, end: usize) -> Self {
+        self.replace_lines(start, end, "")
+    }
+
+    // sed '/pattern/d' with a regex pattern
+    pub fn delete_matching(mut self, pattern: &str) -> Self {
+        self.lines.retain(|line| !crate::utils::str_matches(line, pattern));
+        self
+    }
+
+    /// sed '/start/,/end/d': drops every block from a line matching
+    /// `start_pat` through the next line matching `end_pat`, markers
+    /// included. An unterminated block runs to the end of the stream.
+    pub fn delete_block(mut self, start_pat: &str, end_pat: &str) -> Self {
+        let mut inside = false;
+        self.lines.retain(|line| {
+            if inside {
+                inside = !crate::utils::str_matches(line, end_pat);
+                false
+            } else if crate::utils::str_matches(line, start_pat) {
+                inside = true;
+                false
+            } else {
+                true
+            }
+        });
+        self
+    }
+
+    pub fn sed_around(self, pattern: &str, context: usize) -> Self {
+        Stream::from_string(&crate::fs::sed_around(&self.to_string(), pattern, context))
+    }
+
+    pub fn sed_template(mut self, content: &str, sentinel: &str) -> Self {
+        self.lines = self
+            .lines
+            .iter()
+            .map(|line| line.replace(sentinel, content))
+            .collect();
+        self
+    }
+
+    /// Mustache-lite: replaces every `{{KEY}}` (spaces inside the braces
+    /// allowed) from `vars` in one pass. Unknown keys are left as written.
+    pub fn render_template(self, vars: &HashMap<String, String>) -> Self {
+        self.render_with(|key| vars.get(key).cloned(), false)
+    }
+
+    // Like render_template, but unknown keys render as ""
+    pub fn render_template_blank(self, vars: &HashMap<String, String>) -> Self {
+        self.render_with(|key| vars.get(key).cloned(), true)
+    }
+
+    // render_template against the context vars
+    pub fn render_template_ctx(self) -> Self {
+        self.render_with(|key| crate::context::has_var(key).then(|| get_var(key)), false)
+    }
+
+    fn render_with<F: Fn(&str) -> Option<String>>(mut self, lookup: F, blank_unknown: bool) -> Self {
+        let re = regex::Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_.-]*)\s*\}\}").unwrap();
+        for line in self.lines.iter_mut() {
+            if line.contains("{{") {
+                *line = re
+                    .replace_all(line, |caps: &regex::Captures| match lookup(&caps[1]) {
+                        Some(value) => value,
+                        None if blank_unknown => String::new(),
+                        None => caps[0].to_string(),
+                    })
+                    .into_owned();
+            }
+        }
+        self
+    }
+
+    pub fn cut(mut self, field: usize, delimiter: &str) -> Self {
+        self.lines = self
+            .lines
+            .iter()
+            .filter_map(|line| line.split(delimiter).nth(field.saturating_sub(1)))
+            .map(|s| s.to_string())
+            .collect();
+        self
+    }
+
+    /// Swaps rows and columns: field N of every line becomes line N, joined
+    /// with `delim`. Short rows are padded with empty fields.
+    pub fn transpose(mut self, delim: &str) -> Self {
+        let rows: Vec<Vec<String>> = self.lines.iter().map(|line| split_fields(line, delim)).collect();
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let joiner = if delim.is_empty() { " " } else { delim };
+        self.lines = (0..width)
+            .map(|col| {
+                rows.iter()
+                    .map(|row| row.get(col).map_or("", String::as_str))
+                    .collect::<Vec<_>>()
+                    .join(joiner)
+            })
+            .collect();
+        self
+    }
+
+    // awk-style: the closure gets each line's fields ($1..$N) and returns the
+    // new line. An empty delim splits on runs of whitespace like awk.
+    pub fn map_fields<F>(mut self, delim: &str, mapper: F) -> Self
+    where
+        F: Fn(&[String]) -> String,
+    {
+        self.lines = self.lines.iter().map(|line| mapper(&split_fields(line, delim))).collect();
+        self
+    }
+
+    // Resplit every line on `delim` and flatten, like `tr ',' '\n'`
+    pub fn split_on(mut self, delim: &str) -> Self {
+        self.lines = self
+            .lines
+            .iter()
+            .flat_map(|line| line.split(delim).map(String::from))
+            .collect();
+        self
+    }
+
+    pub fn tr(mut self, from: &str, to: &str) -> Self {
+        let map: Vec<(char, char)> = from.chars().zip(to.chars()).collect();
+        self.lines = self
+            .lines
+            .iter()
+            .map(|line| {
+                line.chars()
+                    .map(|c| map.iter().find(|(f, _)| *f == c).map_or(c, |(_, t)| *t))
+                    .collect()
+            })
+            .collect();
+        self
+    }
+
+    // Strip trailing '\r' from every line (CRLF -> LF)
+    pub fn dos2unix(mut self) -> Self {
+        for line in self.lines.iter_mut() {
+            while line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        self
+    }
+
+    // Give every line a trailing '\r', so joined output is CRLF
+    pub fn unix2dos(mut self) -> Self {
+        for line in self.lines.iter_mut() {
+            if !line.ends_with('\r') {
+                line.push('\r');
+            }
+        }
+        self
+    }
+
+    // Fill $VAR / ${VAR} references on every line from the context
+    pub fn expand_vars(mut self) -> Self {
+        for line in self.lines.iter_mut() {
+            *line = expand_vars(line);
+        }
+        self
+    }
+
+    pub fn trim(mut self) -> Self {
+        self.lines = self.lines.iter().map(|l| l.trim().to_string()).collect();
+        self
+    }
+
+    pub fn head(mut self, n: usize) -> Self {
+        self.lines.truncate(n);
+        self
+    }
+
+    pub fn tail(mut self, n: usize) -> Self {
+        let len = self.lines.len();
+        if len > n {
+            self.lines.drain(..len - n);
+        }
+        self
+    }
+
+    // At most n bytes of content (newlines included), never splitting a character
+    pub fn head_bytes(self, n: usize) -> Self {
+        let content = self.to_string();
+        let mut end = n.min(content.len());
+        while !content.is_char_boundary(end) {
+            end -= 1;
+        }
+        Stream::from_string(&content[..end])
+    }
+
+    pub fn tail_bytes(self, n: usize) -> Self {
+        let content = self.to_string();
+        let mut start = content.len().saturating_sub(n);
+        while !content.is_char_boundary(start) {
+            start += 1;
+        }
+        Stream::from_string(&content[start..])
+    }
+
+    pub fn sort(mut self) -> Self {
+        self.lines.sort();
+        self
+    }
+
+    pub fn reverse(mut self) -> Self {
+        self.lines.reverse();
+        self
+    }
+
+    // Remove duplicates, keeping the first occurrence
+    pub fn unique(mut self) -> Self {
+        let mut seen = std::collections::HashSet::new();
+        self.lines.retain(|line| seen.insert(line.clone()));
+        self
+    }
+
+    // Like unique, but the last occurrence of each line is the one kept
+    pub fn unique_last(self) -> Self {
+        self.unique_last_by_key(|line| line.to_string())
+    }
+
+    // Latest record per key: field `field` (1-indexed, as in cut) decides
+    // what counts as a duplicate; lines without that field are kept
+    pub fn unique_last_by(self, field: usize, delim: &str) -> Self {
+        self.unique_last_by_key(|line| match line.split(delim).nth(field.saturating_sub(1)) {
+            Some(key) => format!("k:{}", key),
+            None => format!("l:{}", line),
+        })
+    }
+
+    fn unique_last_by_key<F: Fn(&str) -> String>(mut self, key: F) -> Self {
+        let mut seen = std::collections::HashSet::new();
+        self.lines.reverse();
+        self.lines.retain(|line| seen.insert(key(line)));
+        self.lines.reverse();
+        self
+    }
+
+    // Collapse adjacent duplicates, like `uniq`
+    pub fn uniq(mut self) -> Self {
+        self.lines.dedup();
+        self
+    }
+
+    pub fn number_lines(mut self) -> Self {
+        self.lines = self
+            .lines
+            .iter()
+            .enumerate()
+            .map(|(i, l)| format!("{:>6}  {}", i + 1, l))
+            .collect();
+        self
+    }
+
+    // Matching lines with `before`/`after` lines of context, like `grep -B -A`;
+    // separate groups are divided by a "--" line as grep does
+    pub fn grep_context(self, pattern: &str, before: usize, after: usize) -> Self {
+        let mut keep = vec![false; self.lines.len()];
+        for (i, line) in self.lines.iter().enumerate() {
+            if line.contains(pattern) {
+                let hi = (i + after).min(self.lines.len() - 1);
+                keep[i.saturating_sub(before)..=hi].iter_mut().for_each(|k| *k = true);
+            }
+        }
+        let mut lines = Vec::new();
+        let mut last: Option<usize> = None;
+        for (i, line) in self.lines.into_iter().enumerate().filter(|(i, _)| keep[*i]) {
+            if last.is_some_and(|l| l + 1 != i) {
+                lines.push("--".to_string());
+            }
+            lines.push(line);
+            last = Some(i);
+        }
+        Stream { lines }
+    }
+
+    pub fn filter<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.lines.retain(|line| predicate(line));
+        self
+    }
+
+    pub fn map<F>(mut self, mapper: F) -> Self
+    where
+        F: Fn(&str) -> String,
+    {
+        self.lines = self.lines.iter().map(|line| mapper(line)).collect();
+        self
+    }
+
+    /// Like `map`, but spreads lines over the rayon thread pool. Output order
+    /// matches the input. Requires the `parallel` feature.
+    #[cfg(feature = "parallel")]
+    pub fn par_map<F>(mut self, mapper: F) -> Self
+    where
+        F: Fn(&str) -> String + Sync + Send,
+    {
+        use rayon::prelude::*;
+        self.lines = self.lines.par_iter().map(|line| mapper(line)).collect();
+        self
+    }
+
+    pub fn filter_map<F>(mut self, mapper: F) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        self.lines = self.lines.iter().filter_map(|line| mapper(line)).collect();
+        self
+    }
+
+    // Like filter_map, but failures are kept: returns the mapped stream and
+    // the error messages of the lines that didn't map, in input order
+    pub fn try_map<F>(self, mapper: F) -> (Self, Vec<String>)
+    where
+        F: Fn(&str) -> Result<String, String>,
+    {
+        let mut mapped = Vec::new();
+        let mut errors = Vec::new();
+        for line in &self.lines {
+            match mapper(line) {
+                Ok(value) => mapped.push(value),
+                Err(e) => errors.push(e),
+            }
+        }
+        (Stream { lines: mapped }, errors)
+    }
+
+    // Split in one pass into (matching, non-matching), order preserved
+    pub fn partition<F>(self, predicate: F) -> (Self, Self)
+    where
+        F: Fn(&str) -> bool,
+    {
+        let (yes, no) = self.lines.into_iter().partition(|line| predicate(line));
+        (Stream { lines: yes }, Stream { lines: no })
+    }
+
+    /// Splits into one stream per section, cutting at lines matching the
+    /// regex `pattern` (e.g. `^---$`). Delimiter lines are dropped, as are
+    /// empty sections. An invalid pattern leaves the stream as one section.
+    pub fn split_sections(self, pattern: &str) -> Vec<Stream> {
+        self.sections_at(pattern, false)
+    }
+
+    /// Like `split_sections`, but each delimiter line opens its section
+    /// (handy when the marker carries data, like `commit <sha>`).
+    pub fn split_sections_keep(self, pattern: &str) -> Vec<Stream> {
+        self.sections_at(pattern, true)
+    }
+
+    fn sections_at(self, pattern: &str, keep: bool) -> Vec<Stream> {
+        let Ok(re) = regex::Regex::new(pattern) else {
+            return vec![self];
+        };
+        let mut sections = Vec::new();
+        let mut current = Vec::new();
+        for line in self.lines {
+            if re.is_match(&line) {
+                if !current.is_empty() {
+                    sections.push(Stream { lines: std::mem::take(&mut current) });
+                }
+                if !keep {
+                    continue;
+                }
+            }
+            current.push(line);
+        }
+        if !current.is_empty() {
+            sections.push(Stream { lines: current });
+        }
+        sections
+    }
+
+    pub fn each<F>(self, mut action: F) -> Self
+    where
+        F: FnMut(&str),
+    {
+        for line in &self.lines {
+            action(line);
+        }
+        self
+    }
+
+    // each() with the 0-based line index
+    pub fn each_with_index<F>(self, mut action: F) -> Self
+    where
+        F: FnMut(usize, &str),
+    {
+        for (i, line) in self.lines.iter().enumerate() {
+            action(i, line);
+        }
+        self
+    }
+
+    /// each() capped at `per_sec` calls per second, sleeping between calls
+    /// as needed (time spent in `action` counts towards the gap). A rate of
+    /// 0 or less doesn't throttle.
+    pub fn each_throttled<F>(self, per_sec: f64, mut action: F) -> Self
+    where
+        F: FnMut(&str),
+    {
+        let gap = (per_sec > 0.0).then(|| std::time::Duration::from_secs_f64(1.0 / per_sec));
+        let mut last: Option<std::time::Instant> = None;
+        for line in &self.lines {
+            if let (Some(gap), Some(last)) = (gap, last) {
+                if let Some(wait) = gap.checked_sub(last.elapsed()) {
+                    std::thread::sleep(wait);
+                }
+            }
+            last = Some(std::time::Instant::now());
+            action(line);
+        }
+        self
+    }
+
+    /// Logs the line count and a short preview under DEBUG/TRACE mode, then
+    /// passes the stream through untouched.
+    pub fn inspect(self, label: &str) -> Self {
+        if crate::utils::should_print_level("debug") {
+            crate::debug!("{}: {} lines", label, self.lines.len());
+            for line in self.lines.iter().take(3) {
+                crate::debug!("{}:   {}", label, line);
+            }
+            if self.lines.len() > 3 {
+                crate::debug!("{}:   ...", label);
+            }
+        }
+        self
+    }
+
+    pub fn tee(self, path: &str) -> Self {
+        write_file(path, &self.clone().to_string());
+        self
+    }
+
+    // Echo each line (with {color} tags expanded) and keep going
+    pub fn tee_stdout(self) -> Self {
+        for line in &self.lines {
+            crate::utils::write_out(&format!("{}\n", crate::utils::expand_colors(line)));
+        }
+        self
+    }
+
+    pub fn tee_stderr(self) -> Self {
+        for line in &self.lines {
+            crate::utils::write_err(&format!("{}\n", crate::utils::expand_colors(line)));
+        }
+        self
+    }
+
+    /// Pipes the stream into a command's stdin and continues with its stdout.
+    pub fn pipe_to_cmd(self, cmd: &str) -> Self {
+        let result = run_cmd_with_input(&expand_vars(cmd), &self.to_string());
+        Stream::from_string(&result.output)
+    }
+
+    /// Unified diff from this stream to `other`, one diff line per stream line.
+    pub fn diff(self, other: &Stream) -> Self {
+        let (mut old, mut new) = (self.to_string(), other.clone().to_string());
+        old.push('\n');
+        new.push('\n');
+        Stream::from_string(&crate::fs::diff_text(&old, &new, "a", "b"))
+    }
+
+    // --- Sinks ---------------------------------------------------------------------
+
+    /// Parses each line as JSON, warning about and skipping lines that fail.
+    /// Blank lines are skipped silently.
+    // Each line's fields, split as in map_fields
+    pub fn fields(self, delim: &str) -> Vec<Vec<String>> {
+        self.lines.iter().map(|line| split_fields(line, delim)).collect()
+    }
+
+    // (line number, line) pairs, numbered from `start`
+    pub fn with_gutter(self, start: usize) -> Vec<(usize, String)> {
+        self.lines.into_iter().enumerate().map(|(i, line)| (start + i, line)).collect()
+    }
+
+    pub fn parse_json(self) -> Vec<serde_json::Value> {
+        let mut values = Vec::new();
+        for (i, line) in self.lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(line) {
+                Ok(value) => values.push(value),
+                Err(e) => crate::warn!("Skipping line {}: invalid JSON ({})", i + 1, e),
+            }
+        }
+        values
+    }
+
+    // The whole stream as one JSON document, flattened to path=value lines
+    pub fn flatten_json(self) -> Self {
+        Stream {
+            lines: crate::os::json_flatten(&self.to_string()),
+        }
+    }
+
+    /// Parses the stream as CSV (quoting and embedded newlines honoured).
+    /// Rows are keyed by header name, or by column index ("0", "1", ...)
+    /// when there is no header.
+    pub fn csv_rows(self, has_header: bool) -> Vec<HashMap<String, String>> {
+        let text = self.to_string();
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(has_header)
+            .flexible(true)
+            .from_reader(text.as_bytes());
+        let headers: Vec<String> = if has_header {
+            reader
+                .headers()
+                .map(|h| h.iter().map(String::from).collect())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        let mut rows = Vec::new();
+        for (i, record) in reader.records().enumerate() {
+            let record = match record {
+                Ok(r) => r,
+                Err(e) => {
+                    crate::warn!("Skipping CSV record {}: {}", i + 1, e);
+                    continue;
+                }
+            };
+            let row = record
+                .iter()
+                .enumerate()
+                .map(|(col, field)| {
+                    let key = headers.get(col).cloned().unwrap_or_else(|| col.to_string());
+                    (key, field.to_string())
+                })
+                .collect();
+            rows.push(row);
+        }
+        rows
+    }
+
+    /// Projects a headed CSV stream onto `columns`, re-quoting as needed.
+    pub fn csv_select(self, columns: &[&str]) -> Self {
+        let rows = self.csv_rows(true);
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(columns).ok();
+        for row in &rows {
+            let fields: Vec<&str> = columns
+                .iter()
+                .map(|c| row.get(*c).map_or("", String::as_str))
+                .collect();
+            writer.write_record(&fields).ok();
+        }
+        let bytes = writer.into_inner().unwrap_or_default();
+        Stream::from_string(&String::from_utf8_lossy(&bytes))
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn to_vec(self) -> Vec<String> {
+        self.lines
+    }
+
+    pub fn to_file(self, path: &str) {
+        let mut content = self.to_string();
+        content.push('\n');
+        write_file(path, &content);
+    }
+
+    // to_file with explicit append/create-dirs/mode control; errors are
+    // returned rather than exiting
+    pub fn write_file_opts(self, path: &str, opts: &crate::fs::WriteOpts) -> crate::error::RsbResult<()> {
+        let mut content = self.to_string();
+        content.push('\n');
+        crate::fs::try_write_file_opts(path, &content, opts)
+    }
+
+    // Inverse of from_file_base64: decodes the lines as base64 and writes
+    // the raw bytes
+    pub fn decode_base64_to_file(self, path: &str) -> crate::error::RsbResult<()> {
+        crate::fs::decode_base64_to_file(&self.lines.concat(), path)
+    }
+
+    pub fn append_to_file(self, path: &str) {
+        let mut content = self.to_string();
+        content.push('\n');
+        append_file(path, &content);
+    }
+
+    #[cfg(feature = "clipboard")]
+    pub fn to_clipboard(self) -> crate::error::RsbResult<()> {
+        clipboard()?
+            .set_text(self.to_string())
+            .map_err(|e| crate::error::RsbError::Clipboard(e.to_string()))
+    }
+
+    // Digest of the content as `to_string` joins it (no trailing newline,
+    // unlike the file to_file writes); empty if no hashing tool is found
+    pub fn sha256(&self) -> String {
+        crate::fs::hash_string(&self.lines.join("\n"), "sha256")
+    }
+
+    pub fn md5(&self) -> String {
+        crate::fs::hash_string(&self.lines.join("\n"), "md5")
+    }
+
+    pub fn to_var(self, name: &str) {
+        crate::context::set_var(name, self.to_string());
+    }
+
+    pub fn count(&self) -> usize {
+        self.lines.len()
+    }
+
+    // Bytes of the content as `to_string` joins it: each line's UTF-8 length
+    // plus one '\n' between lines (no trailing newline)
+    pub fn byte_len(&self) -> usize {
+        let content: usize = self.lines.iter().map(String::len).sum();
+        content + self.lines.len().saturating_sub(1)
+    }
+
+    // Number of lines, the `wc -l` counterpart to byte_len
+    pub fn line_len(&self) -> usize {
+        self.count()
+    }
+
+    // Like `grep -c`
+    pub fn count_matching(&self, pattern: &str) -> usize {
+        self.lines.iter().filter(|line| line.contains(pattern)).count()
+    }
+
+    /// Like `sort | uniq -c | sort -rn`: distinct lines with their counts,
+    /// most frequent first. Ties keep first-seen order.
+    pub fn count_unique(&self) -> Vec<(usize, String)> {
+        let mut counts: Vec<(usize, String)> = Vec::new();
+        let mut index: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+        for line in &self.lines {
+            match index.get(line) {
+                Some(&i) => counts[i].0 += 1,
+                None => {
+                    index.insert(line.clone(), counts.len());
+                    counts.push((1, line.clone()));
+                }
+            }
+        }
+        counts.sort_by_key(|(n, _)| std::cmp::Reverse(*n));
+        counts
+    }
+
+    // Numbers in `field` (1-based, 0 for the whole line; empty delim splits
+    // on whitespace), skipping lines where it doesn't parse
+    fn numbers(&self, field: usize, delim: &str) -> Vec<f64> {
+        self.lines
+            .iter()
+            .filter_map(|line| match field {
+                0 => line.trim().parse().ok(),
+                n => split_fields(line, delim).get(n - 1)?.trim().parse().ok(),
+            })
+            .collect()
+    }
+
+    /// Sum of the numeric values in `field` (0 = whole line), like
+    /// `awk '{s+=$N} END {print s}'`. Non-numeric lines are skipped.
+    pub fn sum_numeric(&self, field: usize, delim: &str) -> f64 {
+        self.numbers(field, delim).iter().sum()
+    }
+
+    pub fn min_numeric(&self, field: usize, delim: &str) -> Option<f64> {
+        self.numbers(field, delim).into_iter().reduce(f64::min)
+    }
+
+    pub fn max_numeric(&self, field: usize, delim: &str) -> Option<f64> {
+        self.numbers(field, delim).into_iter().reduce(f64::max)
+    }
+
+    // None when no line held a number
+    pub fn avg_numeric(&self, field: usize, delim: &str) -> Option<f64> {
+        let numbers = self.numbers(field, delim);
+        (!numbers.is_empty()).then(|| numbers.iter().sum::<f64>() / numbers.len() as f64)
+    }
+
+    pub fn any<F>(&self, predicate: F) -> bool
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.lines.iter().any(|line| predicate(line))
+    }
+
+    pub fn all<F>(&self, predicate: F) -> bool
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.lines.iter().all(|line| predicate(line))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn first(&self) -> Option<&String> {
+        self.lines.first()
+    }
+
+    pub fn last(&self) -> Option<&String> {
+        self.lines.last()
+    }
+}
+
+// One line per item: `Stream::from_iter(names)` or `iter.collect::<Stream>()`
+impl<S: Into<String>> FromIterator<S> for Stream {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Stream {
+            lines: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+fn split_fields(line: &str, delim: &str) -> Vec<String> {
+    if delim.is_empty() {
+        line.split_whitespace().map(String::from).collect()
+    } else {
+        line.split(delim).map(String::from).collect()
+    }
+}
+
+#[cfg(feature = "clipboard")]
+fn clipboard() -> crate::error::RsbResult<arboard::Clipboard> {
+    arboard::Clipboard::new().map_err(|e| crate::error::RsbError::Clipboard(e.to_string()))
+}
@@ -0,0 +1,29 @@
+//! In-process harness for exercising dispatch handlers: output is captured
+//! and the exit code returned instead of spawning a binary.
+
+/// Outcome of one in-process run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunResult {
+    pub code: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Runs `dispatch` (typically a `try_dispatch!`) with `args` as the command
+/// line after the program name, capturing echo!/printf! and leveled output.
+///
+/// ```ignore
+/// let run = rsb::testing::run(&["greet", "bob"], |argv| try_dispatch!(argv, { "greet" => do_greet }));
+/// assert_eq!(run.stdout, "Hello, bob!\n");
+/// ```
+pub fn run<F>(args: &[&str], dispatch: F) -> RunResult
+where
+    F: FnOnce(&[String]) -> i32,
+{
+    let argv: Vec<String> = std::iter::once("rsb-test")
+        .chain(args.iter().copied())
+        .map(String::from)
+        .collect();
+    let (code, stdout, stderr) = crate::utils::capture(|| dispatch(&argv));
+    RunResult { code, stdout, stderr }
+}
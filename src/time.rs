@@ -0,0 +1,98 @@
+//! Dates, sleeping and timing.
+
+use chrono::Local;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+pub fn current_epoch() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn current_epoch_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+/// Formats the local time with a chrono/strftime format string.
+pub fn date_format(fmt: &str) -> String {
+    Local::now().format(fmt).to_string()
+}
+
+pub fn date_iso() -> String {
+    Local::now().to_rfc3339()
+}
+
+pub fn date_human() -> String {
+    date_format("%Y-%m-%d %H:%M:%S")
+}
+
+pub fn sleep_secs(secs: u64) {
+    std::thread::sleep(Duration::from_secs(secs));
+}
+
+pub fn sleep_ms(ms: u64) {
+    std::thread::sleep(Duration::from_millis(ms));
+}
+
+/// Sleeps until the wall clock reaches `epoch_ms`. Wakes early if a SIGINT
+/// arrives while an `InterruptGuard` is installed.
+pub fn sleep_until(epoch_ms: u128) {
+    loop {
+        let now = current_epoch_ms();
+        if now >= epoch_ms || INTERRUPTED.load(Ordering::SeqCst) {
+            return;
+        }
+        let remaining = (epoch_ms - now).min(50) as u64;
+        std::thread::sleep(Duration::from_millis(remaining));
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn on_sigint(_: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Catches SIGINT while alive so loops like `until!` can stop cleanly;
+/// the previous handler is restored on drop.
+pub struct InterruptGuard {
+    #[cfg(unix)]
+    previous: libc::sighandler_t,
+}
+
+impl InterruptGuard {
+    pub fn install() -> Self {
+        INTERRUPTED.store(false, Ordering::SeqCst);
+        #[cfg(unix)]
+        {
+            let handler = on_sigint as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            // SAFETY: the handler only touches an atomic.
+            let previous = unsafe { libc::signal(libc::SIGINT, handler) };
+            InterruptGuard { previous }
+        }
+        #[cfg(not(unix))]
+        {
+            InterruptGuard {}
+        }
+    }
+
+    pub fn interrupted(&self) -> bool {
+        INTERRUPTED.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        // SAFETY: restores the handler that was active before install().
+        unsafe {
+            libc::signal(libc::SIGINT, self.previous);
+        }
+    }
+}
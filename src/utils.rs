@@ -0,0 +1,801 @@
+//! General helpers: stderr messaging, color expansion, context arrays,
+//! bash-style string manipulation and interactive prompts.
+
+use crate::context::{expand_vars, get_color, get_glyph, get_var, has_var, set_var, COLORS};
+use crate::visual::width_plugin::{char_width, get_display_width};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::sync::Mutex;
+
+// --- Output sinks ------------------------------------------------------------
+
+thread_local! {
+    // (stdout, stderr) buffers while `capture` runs on this thread
+    static CAPTURED: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+    // Nesting depth of `quietly` on this thread
+    static QUIET_DEPTH: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+fn quiet_here() -> bool {
+    QUIET_DEPTH.with(|d| d.get() > 0)
+}
+
+/// Runs `f` with this thread's echo!/printf! output and non-error messages
+/// dropped; errors still reach stderr.
+pub fn quietly<R>(f: impl FnOnce() -> R) -> R {
+    struct Restore;
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            QUIET_DEPTH.with(|d| d.set(d.get() - 1));
+        }
+    }
+    QUIET_DEPTH.with(|d| d.set(d.get() + 1));
+    let _restore = Restore;
+    f()
+}
+
+/// Writes to stdout, or to the capture buffer inside `capture`. Dropped
+/// inside `quietly` or when RSB_QUIET_STDOUT is set; QUIET_MODE only
+/// silences stderr messages.
+pub fn write_out(text: &str) {
+    if quiet_here() || has_var("RSB_QUIET_STDOUT") {
+        return;
+    }
+    let captured = CAPTURED.with(|c| c.borrow_mut().as_mut().map(|(out, _)| out.push_str(text)));
+    if captured.is_none() {
+        print!("{}", text);
+        io::stdout().flush().ok();
+    }
+}
+
+/// Writes to stderr, or to the capture buffer inside `capture`.
+pub fn write_err(text: &str) {
+    let captured = CAPTURED.with(|c| c.borrow_mut().as_mut().map(|(_, err)| err.push_str(text)));
+    if captured.is_none() {
+        eprint!("{}", text);
+    }
+}
+
+/// Runs `f` with this thread's echo!/printf!/leveled-message output
+/// collected instead of printed; returns the result with (stdout, stderr).
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, String, String) {
+    let outer = CAPTURED.with(|c| c.borrow_mut().replace((String::new(), String::new())));
+    let result = f();
+    let (out, err) = CAPTURED.with(|c| std::mem::replace(&mut *c.borrow_mut(), outer)).unwrap_or_default();
+    (result, out, err)
+}
+
+// --- stderr messaging ------------------------------------------------------
+
+pub fn should_print_level(level: &str) -> bool {
+    if (quiet_here() || has_var("QUIET_MODE")) && !["error", "fatal"].contains(&level) {
+        return false;
+    }
+
+    match level {
+        "trace" => has_var("TRACE_MODE"),
+        "debug" => has_var("DEBUG_MODE") || has_var("TRACE_MODE"),
+        _ => true,
+    }
+}
+
+fn level_style(level: &str) -> (&'static str, &'static str) {
+    match level {
+        "okay" => ("green", "pass"),
+        "warn" => ("yellow", "warn"),
+        "error" => ("red", "fail"),
+        "fatal" => ("red", "fatal"),
+        "debug" => ("grey", "debug"),
+        "trace" => ("grey", "trace"),
+        _ => ("blue", "info"),
+    }
+}
+
+/// Prints a leveled message to stderr with its glyph and color.
+pub fn stderrx(level: &str, message: &str) {
+    if !should_print_level(level) {
+        return;
+    }
+    let (color, glyph) = level_style(level);
+    let line = format!(
+        "{}{}{} {}",
+        get_color(color),
+        get_glyph(glyph),
+        get_color("reset"),
+        expand_colors(&expand_vars(message))
+    );
+    write_err(&format!("{}\n", line));
+}
+
+/// A `width`-column rule with `title` centred in it (`──── Build ────`),
+/// the title in cyan and truncated if it can't fit.
+pub fn section_header(title: &str, width: usize) -> String {
+    let title = expand_vars(title);
+    if title.is_empty() {
+        return str_line('─', width);
+    }
+    let label = format!(" {} ", str_truncate(&title, width.saturating_sub(4), "…"));
+    let fill = width.saturating_sub(get_display_width(&label));
+    let left = fill / 2;
+    format!("{}{}{}", str_line('─', left), colorize(&label, "cyan"), str_line('─', fill - left))
+}
+
+/// Prints a terminal-wide `section_header` to stderr, like an info message
+/// (so QUIET_MODE hides it).
+pub fn section(title: &str) {
+    if !should_print_level("info") {
+        return;
+    }
+    let width = crate::visual::width_plugin::get_terminal_width();
+    write_err(&format!("{}\n", section_header(title, width)));
+}
+
+/// Replaces `{name}` color tags with their escape codes.
+pub fn expand_colors(text: &str) -> String {
+    if !text.contains('{') {
+        return text.to_string();
+    }
+    let mut result = text.to_string();
+    for (name, code) in COLORS.lock().unwrap().iter() {
+        result = result.replace(&format!("{{{}}}", name), code);
+    }
+    result
+}
+
+/// Wraps `text` in a color (base palette, then extended) and a reset.
+/// Resets already inside `text` re-apply the color, so nested calls resume
+/// the outer color. Unknown names, or colors turned off, return `text` as is.
+pub fn colorize(text: &str, color: &str) -> String {
+    let reset = get_color("reset");
+    if reset.is_empty() {
+        return text.to_string();
+    }
+    let code = match get_color(color) {
+        code if !code.is_empty() => code,
+        _ => match crate::visual::extended_colors::get_extended_color(color) {
+            Some(code) => code.to_string(),
+            None => return text.to_string(),
+        },
+    };
+    format!("{}{}{}", code, text.replace(&reset, &format!("{}{}", reset, code)), reset)
+}
+
+// --- Arrays ------------------------------------------------------------------
+
+pub fn set_array(key: &str, items: &[&str]) {
+    set_var(format!("{}_LENGTH", key), items.len().to_string());
+    for (i, item) in items.iter().enumerate() {
+        set_var(format!("{}_{}", key, i), *item);
+    }
+    // Store the whole array as a space-separated string too
+    set_var(key, items.join(" "));
+}
+
+pub fn get_array(key: &str) -> Vec<String> {
+    let length_key = format!("{}_LENGTH", key);
+    if !has_var(&length_key) {
+        return get_var(key)
+            .split_whitespace()
+            .map(|s| s.to_string())
+            .collect();
+    }
+
+    let length: usize = get_var(&length_key).parse().unwrap_or(0);
+    (0..length)
+        .map(|i| format!("{}_{}", key, i))
+        .filter(|k| has_var(k))
+        .map(|k| get_var(&k))
+        .collect()
+}
+
+pub fn array_push(key: &str, item: &str) {
+    let mut items = get_array(key);
+    items.push(item.to_string());
+    let refs: Vec<&str> = items.iter().map(String::as_str).collect();
+    set_array(key, &refs);
+}
+
+pub fn array_get(key: &str, index: usize) -> String {
+    get_array(key).get(index).cloned().unwrap_or_default()
+}
+
+pub fn array_length(key: &str) -> usize {
+    get_array(key).len()
+}
+
+pub fn array_contains(key: &str, item: &str) -> bool {
+    get_array(key).iter().any(|i| i == item)
+}
+
+// Set algebra over two context arrays. Results are de-duplicated and keep
+// the first array's order (union then appends the second's new items).
+pub fn array_union(a: &str, b: &str) -> Vec<String> {
+    let mut result = array_set_op(a, |_| true);
+    for item in get_array(b) {
+        if !result.contains(&item) {
+            result.push(item);
+        }
+    }
+    result
+}
+
+pub fn array_intersect(a: &str, b: &str) -> Vec<String> {
+    let other = get_array(b);
+    array_set_op(a, |item| other.contains(item))
+}
+
+pub fn array_difference(a: &str, b: &str) -> Vec<String> {
+    let other = get_array(b);
+    array_set_op(a, |item| !other.contains(item))
+}
+
+fn array_set_op<F: Fn(&String) -> bool>(a: &str, keep: F) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for item in get_array(a) {
+        if keep(&item) && !result.contains(&item) {
+            result.push(item);
+        }
+    }
+    result
+}
+
+// --- String manipulation ------------------------------------------------------
+
+// ${VAR:offset:length} - character based. A negative offset counts from the
+// end like ${VAR: -3}; offsets outside the string give "".
+pub fn str_sub(value: &str, offset: isize, length: Option<usize>) -> String {
+    let count = value.chars().count();
+    let start = if offset < 0 {
+        match count.checked_sub(offset.unsigned_abs()) {
+            Some(start) => start,
+            None => return String::new(),
+        }
+    } else {
+        offset as usize
+    };
+    let chars = value.chars().skip(start);
+    match length {
+        Some(len) => chars.take(len).collect(),
+        None => chars.collect(),
+    }
+}
+
+fn glob_to_regex(pattern: &str, greedy: bool) -> String {
+    let star = if greedy { ".*" } else { ".*?" };
+    let mut out = String::new();
+    for c in pattern.chars() {
+        match c {
+            '*' => out.push_str(star),
+            '?' => out.push('.'),
+            _ => out.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    out
+}
+
+// ${VAR#pattern} / ${VAR##pattern}
+pub fn str_prefix(value: &str, pattern: &str, longest: bool) -> String {
+    let re = Regex::new(&format!("^{}", glob_to_regex(pattern, longest))).unwrap();
+    match re.find(value) {
+        Some(m) => value[m.end()..].to_string(),
+        None => value.to_string(),
+    }
+}
+
+// ${VAR%pattern} / ${VAR%%pattern}
+pub fn str_suffix(value: &str, pattern: &str, longest: bool) -> String {
+    let re = Regex::new(&format!("^(?:{})$", glob_to_regex(pattern, true))).unwrap();
+    let starts: Vec<usize> = value
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(value.len()))
+        .collect();
+    let mut candidates: Box<dyn Iterator<Item = &usize>> = if longest {
+        Box::new(starts.iter())
+    } else {
+        Box::new(starts.iter().rev())
+    };
+    match candidates.find(|&&i| re.is_match(&value[i..])) {
+        Some(&i) => value[..i].to_string(),
+        None => value.to_string(),
+    }
+}
+
+// ${VAR/from/to} / ${VAR//from/to}
+pub fn str_replace(value: &str, from: &str, to: &str, all: bool) -> String {
+    if all {
+        value.replace(from, to)
+    } else {
+        value.replacen(from, to, 1)
+    }
+}
+
+// ${VAR^^} / ${VAR^}
+pub fn str_upper(value: &str, first_only: bool) -> String {
+    if !first_only {
+        return value.to_uppercase();
+    }
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+// ${VAR,,} / ${VAR,}
+pub fn str_lower(value: &str, first_only: bool) -> String {
+    if !first_only {
+        return value.to_lowercase();
+    }
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(c) => c.to_lowercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+lazy_static! {
+    static ref REGEX_CACHE: Mutex<HashMap<String, Regex>> = Mutex::new(HashMap::new());
+}
+
+// Bound on cached patterns; the cache is simply cleared when it fills
+const REGEX_CACHE_LIMIT: usize = 256;
+
+// Compiled patterns are cached so per-line matching doesn't recompile
+fn cached_regex(pattern: &str) -> Option<Regex> {
+    let mut cache = REGEX_CACHE.lock().unwrap();
+    if let Some(re) = cache.get(pattern) {
+        return Some(re.clone());
+    }
+    let re = Regex::new(pattern).ok()?;
+    if cache.len() >= REGEX_CACHE_LIMIT {
+        cache.clear();
+    }
+    cache.insert(pattern.to_string(), re.clone());
+    Some(re)
+}
+
+// [[ value =~ pattern ]]
+pub fn str_matches(value: &str, pattern: &str) -> bool {
+    match cached_regex(pattern) {
+        Some(re) => re.is_match(value),
+        None => false,
+    }
+}
+
+/// Matches `pattern` against `text` and stores each named group as
+/// `{prefix}_{name}` (just `{name}` with an empty prefix). Groups that didn't
+/// take part are set to "". Returns false, setting nothing, on no match or an
+/// invalid pattern.
+pub fn capture_vars(pattern: &str, text: &str, prefix: &str) -> bool {
+    let Some(re) = cached_regex(pattern) else {
+        return false;
+    };
+    let Some(caps) = re.captures(text) else {
+        return false;
+    };
+    for name in re.capture_names().flatten() {
+        let key = if prefix.is_empty() { name.to_string() } else { format!("{}_{}", prefix, name) };
+        set_var(key, caps.name(name).map_or("", |m| m.as_str()));
+    }
+    true
+}
+
+pub fn str_line(ch: char, count: usize) -> String {
+    ch.to_string().repeat(count)
+}
+
+// Padding and truncation measure display width, so ANSI codes and wide
+// characters line up the way they render.
+pub fn str_pad_left(value: &str, width: usize, fill: char) -> String {
+    let pad = width.saturating_sub(get_display_width(value));
+    format!("{}{}", fill.to_string().repeat(pad), value)
+}
+
+pub fn str_pad_right(value: &str, width: usize, fill: char) -> String {
+    let pad = width.saturating_sub(get_display_width(value));
+    format!("{}{}", value, fill.to_string().repeat(pad))
+}
+
+// Extra space goes on the right when the padding is uneven
+pub fn str_center(value: &str, width: usize) -> String {
+    let pad = width.saturating_sub(get_display_width(value));
+    format!("{}{}{}", " ".repeat(pad / 2), value, " ".repeat(pad - pad / 2))
+}
+
+pub fn str_truncate(value: &str, max: usize, ellipsis: &str) -> String {
+    if get_display_width(value) <= max {
+        return value.to_string();
+    }
+    let budget = max.saturating_sub(get_display_width(ellipsis));
+    let mut out = String::new();
+    let mut used = 0;
+    let mut saw_ansi = false;
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        // Escape sequences are copied through without using any width
+        if c == '\x1b' {
+            saw_ansi = true;
+            out.push(c);
+            while let Some(&next) = chars.peek() {
+                out.push(next);
+                chars.next();
+                if next.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+            continue;
+        }
+        let w = char_width(c);
+        if used + w > budget {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    if saw_ansi {
+        out.push_str("\x1b[0m");
+    }
+    out.push_str(ellipsis);
+    out
+}
+
+/// Removes the indentation common to all non-blank lines, plus a leading
+/// blank line and trailing indent, so indented literals read naturally.
+pub fn dedent(text: &str) -> String {
+    let text = text.strip_prefix('\n').unwrap_or(text);
+    let text = text.trim_end_matches([' ', '\t']);
+    let indent = text
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+    let mut out = text
+        .lines()
+        .map(|l| l.get(indent..).unwrap_or_else(|| l.trim_start()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+pub fn str_explode(value: &str, delim: &str, into: &str) {
+    let items: Vec<&str> = value.split(delim).collect();
+    set_array(into, &items);
+}
+
+pub fn str_join(key: &str, delim: &str) -> String {
+    get_array(key).join(delim)
+}
+
+pub fn is_numeric(value: &str) -> bool {
+    value.trim().parse::<f64>().is_ok()
+}
+
+// Numeric comparison for test!(-gt/-lt/...); None if either side isn't a number
+pub fn num_cmp(a: &str, b: &str) -> Option<std::cmp::Ordering> {
+    let a = a.trim().parse::<f64>().ok()?;
+    let b = b.trim().parse::<f64>().ok()?;
+    a.partial_cmp(&b)
+}
+
+pub fn is_empty(value: &str) -> bool {
+    value.trim().is_empty()
+}
+
+// --- Encoding -----------------------------------------------------------------
+
+pub fn base64_encode(value: &str) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(value)
+}
+
+pub fn base64_decode(value: &str) -> Result<String, String> {
+    use base64::Engine;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(value.trim())
+        .map_err(|e| format!("Invalid base64: {}", e))?;
+    String::from_utf8(bytes).map_err(|_| "Decoded base64 is not valid UTF-8".to_string())
+}
+
+pub fn hex_encode(value: &str) -> String {
+    value.bytes().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn hex_decode(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    if !value.len().is_multiple_of(2) {
+        return Err("Invalid hex: odd number of digits".to_string());
+    }
+    let bytes = (0..value.len())
+        .step_by(2)
+        .map(|i| {
+            value
+                .get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| format!("Invalid hex digit at offset {}", i))
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+    String::from_utf8(bytes).map_err(|_| "Decoded hex is not valid UTF-8".to_string())
+}
+
+// --- Byte sizes -----------------------------------------------------------------
+
+/// Human-readable size: "1.5 MiB" with `binary` (powers of 1024), "1.5 MB"
+/// otherwise (powers of 1000). Below one unit it's plain bytes ("512 B").
+pub fn format_bytes(n: u64, binary: bool) -> String {
+    let (base, units) = if binary {
+        (1024.0, ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"])
+    } else {
+        (1000.0, ["KB", "MB", "GB", "TB", "PB", "EB"])
+    };
+    if (n as f64) < base {
+        return format!("{} B", n);
+    }
+    let mut value = n as f64;
+    let mut unit = "B";
+    for u in units {
+        if value < base {
+            break;
+        }
+        value /= base;
+        unit = u;
+    }
+    format!("{:.1} {}", value, unit)
+}
+
+/// Parses sizes like "512", "1.5MB", "2 KiB" or "10k". `KB`-style units are
+/// decimal and `KiB`-style binary; a bare letter (`k`, `M`) is binary, as
+/// in `du -h`. Case-insensitive.
+pub fn parse_bytes(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("Invalid size: {}", value))?;
+    let unit = unit.trim().to_lowercase();
+    let power = match unit.chars().next() {
+        None | Some('b') if unit.len() <= 1 => 0,
+        Some('k') => 1,
+        Some('m') => 2,
+        Some('g') => 3,
+        Some('t') => 4,
+        Some('p') => 5,
+        Some('e') => 6,
+        _ => return Err(format!("Unknown size unit: {}", unit)),
+    };
+    let base: f64 = match unit.get(1..) {
+        Some("b") => 1000.0,
+        Some("") | Some("ib") | Some("i") | None => 1024.0,
+        _ => return Err(format!("Unknown size unit: {}", unit)),
+    };
+    let bytes = number * base.powi(power);
+    if bytes > u64::MAX as f64 {
+        return Err(format!("Size too large: {}", value));
+    }
+    Ok(bytes.round() as u64)
+}
+
+// --- Interactive input --------------------------------------------------------
+
+pub fn readline() -> String {
+    read_line_opt().unwrap_or_default()
+}
+
+// None at EOF, so callers can tell "closed" from "empty line"
+fn read_line_opt() -> Option<String> {
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim_end_matches(['\n', '\r']).to_string()),
+    }
+}
+
+lazy_static! {
+    // Reader left blocked on stdin by a timed-out readline_timeout
+    static ref PENDING_READ: Mutex<Option<std::sync::mpsc::Receiver<Option<String>>>> = Mutex::new(None);
+}
+
+/// Reads a line, giving up after `secs` seconds and returning `default`
+/// (also returned at EOF). stdin can't be un-read, so on timeout the reader
+/// thread stays blocked; the next timed read picks up its line instead of
+/// starting another. Limitation: text the user was half-way through typing
+/// is dropped from the terminal's line buffer on a tty, but a line finished
+/// after the deadline goes to the next timed read.
+pub fn readline_timeout(secs: u64, default: &str) -> String {
+    match read_line_within(std::time::Duration::from_secs(secs)) {
+        Ok(Some(line)) => line,
+        Ok(None) => default.to_string(),
+        Err(_) => {
+            discard_typed_input();
+            eprintln!();
+            default.to_string()
+        }
+    }
+}
+
+// Ok(None) at EOF, Err on timeout (the reader is kept for the next call)
+fn read_line_within(wait: std::time::Duration) -> Result<Option<String>, std::sync::mpsc::RecvTimeoutError> {
+    use std::sync::mpsc::RecvTimeoutError;
+
+    let mut pending = PENDING_READ.lock().unwrap();
+    let rx = pending.take().unwrap_or_else(|| {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            tx.send(read_line_opt()).ok();
+        });
+        rx
+    });
+    match rx.recv_timeout(wait) {
+        Ok(line) => Ok(line),
+        Err(RecvTimeoutError::Disconnected) => Ok(None),
+        Err(RecvTimeoutError::Timeout) => {
+            *pending = Some(rx);
+            Err(RecvTimeoutError::Timeout)
+        }
+    }
+}
+
+// Drop a half-typed line so it doesn't resurface at the next prompt
+fn discard_typed_input() {
+    #[cfg(unix)]
+    // SAFETY: plain syscalls on stdin; both are no-ops when it isn't a tty.
+    unsafe {
+        if libc::isatty(libc::STDIN_FILENO) == 1 {
+            libc::tcflush(libc::STDIN_FILENO, libc::TCIFLUSH);
+        }
+    }
+}
+
+/// Reads several lines: until EOF or a line equal to `sentinel`. With
+/// `use_editor`, and `$VISUAL`/`$EDITOR` set and a terminal on stdin, the
+/// editor is opened on a temp file instead (like `git commit`) and its
+/// saved contents returned. Trailing blank lines are dropped.
+pub fn prompt_multiline(prompt: &str, sentinel: &str, use_editor: bool) -> String {
+    use std::io::IsTerminal;
+
+    let editor = [get_var("VISUAL"), get_var("EDITOR")]
+        .into_iter()
+        .chain(["VISUAL", "EDITOR"].iter().filter_map(|k| std::env::var(k).ok()))
+        .find(|e| !e.trim().is_empty());
+    if let Some(editor) = editor.filter(|_| use_editor && io::stdin().is_terminal()) {
+        let path = crate::fs::create_temp_file_path("random");
+        std::fs::write(&path, "").ok();
+        // The editor needs the real terminal, so stdio is inherited rather
+        // than captured as run_cmd_with_status does
+        let status = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} {}", editor, crate::os::sh_quote(&path)))
+            .status();
+        let text = crate::fs::read_file(&path);
+        std::fs::remove_file(&path).ok();
+        if status.is_ok_and(|s| s.success()) {
+            return text.trim_end().to_string();
+        }
+        crate::warn!("Editor '{}' failed; reading from stdin", editor);
+    }
+
+    if !prompt.is_empty() {
+        eprintln!("{}", expand_colors(&expand_vars(prompt)));
+    }
+    let mut lines = Vec::new();
+    while let Some(line) = read_line_opt() {
+        if line == sentinel {
+            break;
+        }
+        lines.push(line);
+    }
+    lines.join("\n").trim_end().to_string()
+}
+
+pub fn prompt_user(prompt: &str, default: Option<&str>) -> String {
+    match default {
+        Some(d) => eprint!("{} [{}]: ", expand_colors(&expand_vars(prompt)), d),
+        None => eprint!("{}: ", expand_colors(&expand_vars(prompt))),
+    }
+    io::stderr().flush().ok();
+
+    let answer = readline();
+    if answer.trim().is_empty() {
+        default.unwrap_or("").to_string()
+    } else {
+        answer.trim().to_string()
+    }
+}
+
+fn assume_yes() -> bool {
+    let truthy = |v: &str| matches!(v, "1" | "true" | "yes");
+    get_var("opt_yes") == "1"
+        || truthy(&get_var("RSB_ASSUME_YES"))
+        || std::env::var("RSB_ASSUME_YES").is_ok_and(|v| truthy(&v))
+}
+
+/// Asks yes/no with a visible countdown; returns `default` when the time
+/// runs out, at EOF, or straight away under --yes/RSB_ASSUME_YES. An
+/// unrecognised answer also counts as the default.
+pub fn confirm_timeout(prompt: &str, secs: u64, default: bool) -> bool {
+    if assume_yes() {
+        return default;
+    }
+    let hint = if default { "[Y/n]" } else { "[y/N]" };
+    let prompt = expand_colors(&expand_vars(prompt));
+    for remaining in (1..=secs.max(1)).rev() {
+        eprint!("\r\x1b[2K{} {} ({}s) ", prompt, hint, remaining);
+        io::stderr().flush().ok();
+        match read_line_within(std::time::Duration::from_secs(1)) {
+            Ok(Some(answer)) => {
+                return match answer.trim().to_lowercase().as_str() {
+                    "y" | "yes" => true,
+                    "n" | "no" => false,
+                    _ => default,
+                };
+            }
+            Ok(None) => break,
+            Err(_) => {}
+        }
+    }
+    discard_typed_input();
+    eprintln!();
+    default
+}
+
+/// Asks a yes/no question. `opt_yes` or `RSB_ASSUME_YES` answer yes up front;
+/// without a TTY an unusable answer or EOF falls back to the default (or no).
+pub fn confirm_action(prompt: &str, default: Option<bool>) -> bool {
+    use std::io::IsTerminal;
+
+    if assume_yes() {
+        return true;
+    }
+
+    let hint = match default {
+        Some(true) => "[Y/n]",
+        Some(false) => "[y/N]",
+        None => "[y/n]",
+    };
+    let interactive = io::stdin().is_terminal();
+
+    loop {
+        eprint!("{} {} ", expand_colors(&expand_vars(prompt)), hint);
+        io::stderr().flush().ok();
+
+        let Some(answer) = read_line_opt() else {
+            eprintln!();
+            return default.unwrap_or(false);
+        };
+        match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => return true,
+            "n" | "no" => return false,
+            "" if default.is_some() => return default.unwrap_or(false),
+            _ if !interactive => return default.unwrap_or(false),
+            _ => {}
+        }
+    }
+}
+
+/// Guards an irreversible action: the user must type `expected` exactly
+/// (a plain "y" is refused). Only an explicit `--force` (`opt_force`) skips
+/// the prompt; `--yes`/RSB_ASSUME_YES deliberately don't. EOF refuses.
+pub fn confirm_destructive(prompt: &str, expected: &str) -> bool {
+    if get_var("opt_force") == "1" {
+        return true;
+    }
+    eprint!("{} Type '{}' to continue: ", expand_colors(&expand_vars(prompt)), expected);
+    io::stderr().flush().ok();
+    match read_line_opt() {
+        Some(answer) => answer.trim() == expected,
+        None => {
+            eprintln!();
+            false
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Extended 256-color palette and theme suggestions.
+
+pub const EXTENDED_COLORS: &[(&str, &str)] = &[
+    ("orange", "\x1b[38;5;208m"),
+    ("amber", "\x1b[38;5;214m"),
+    ("gold", "\x1b[38;5;220m"),
+    ("lime", "\x1b[38;5;118m"),
+    ("emerald", "\x1b[38;5;34m"),
+    ("teal", "\x1b[38;5;30m"),
+    ("sky", "\x1b[38;5;117m"),
+    ("azure", "\x1b[38;5;33m"),
+    ("navy", "\x1b[38;5;18m"),
+    ("purple", "\x1b[38;5;129m"),
+    ("violet", "\x1b[38;5;177m"),
+    ("pink", "\x1b[38;5;205m"),
+    ("crimson", "\x1b[38;5;160m"),
+    ("silver", "\x1b[38;5;250m"),
+    ("slate", "\x1b[38;5;66m"),
+    ("bright_red", "\x1b[91m"),
+    ("bright_green", "\x1b[92m"),
+    ("bright_yellow", "\x1b[93m"),
+    ("bright_blue", "\x1b[94m"),
+    ("bright_white", "\x1b[97m"),
+];
+
+pub fn get_extended_color(name: &str) -> Option<&'static str> {
+    EXTENDED_COLORS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, code)| *code)
+}
+
+/// Suggested palette entries per message category, used when choosing a theme.
+pub fn generate_kb_theme_suggestions() -> Vec<(&'static str, Vec<&'static str>)> {
+    vec![
+        ("error", vec!["crimson", "bright_red", "orange"]),
+        ("warning", vec!["amber", "gold", "bright_yellow"]),
+        ("success", vec!["emerald", "lime", "bright_green"]),
+        ("info", vec!["azure", "sky", "bright_blue"]),
+        ("debug", vec!["slate", "silver", "violet"]),
+        ("accent", vec!["purple", "pink", "teal"]),
+    ]
+}
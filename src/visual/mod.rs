@@ -0,0 +1,6 @@
+//! Terminal presentation helpers: width measurement, the extended color
+//! palette and progress indicators.
+
+pub mod extended_colors;
+pub mod progress;
+pub mod width_plugin;
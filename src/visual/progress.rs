@@ -0,0 +1,58 @@
+//! Spinner progress indicator drawn on stderr.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+const FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// A running spinner; stops and clears its line on `stop()` or drop.
+pub struct Spinner {
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Spinner {
+    /// Starts drawing `message` with a spinner. Draws nothing under QUIET_MODE.
+    pub fn start(message: &str) -> Self {
+        let running = Arc::new(AtomicBool::new(true));
+        if crate::context::has_var("QUIET_MODE") {
+            return Spinner {
+                running,
+                handle: None,
+            };
+        }
+        let flag = Arc::clone(&running);
+        let message = message.to_string();
+        let handle = std::thread::spawn(move || {
+            let mut frame = 0;
+            while flag.load(Ordering::SeqCst) {
+                eprint!("\r{} {}", FRAMES[frame % FRAMES.len()], message);
+                std::io::stderr().flush().ok();
+                frame += 1;
+                std::thread::sleep(Duration::from_millis(80));
+            }
+            eprint!("\r\x1b[2K");
+            std::io::stderr().flush().ok();
+        });
+        Spinner {
+            running,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,79 @@
+//! Terminal and display width measurement.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref ANSI_PATTERN: Regex = Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]").unwrap();
+}
+
+/// Terminal columns from `COLUMNS`, the tty, or a fallback of 80.
+pub fn get_terminal_width() -> usize {
+    if let Some(cols) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.trim().parse::<usize>().ok())
+        .filter(|c| *c > 0)
+    {
+        return cols;
+    }
+    tty_width().unwrap_or(80)
+}
+
+/// Re-reads the terminal width and stores it in the `COLS` context var.
+pub fn refresh_cols() -> usize {
+    let cols = get_terminal_width();
+    crate::context::set_var("COLS", cols.to_string());
+    cols
+}
+
+#[cfg(unix)]
+fn tty_width() -> Option<usize> {
+    for fd in [libc::STDOUT_FILENO, libc::STDERR_FILENO, libc::STDIN_FILENO] {
+        let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+        // SAFETY: TIOCGWINSZ only writes into the winsize struct we own.
+        let rc = unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut ws) };
+        if rc == 0 && ws.ws_col > 0 {
+            return Some(ws.ws_col as usize);
+        }
+    }
+    None
+}
+
+#[cfg(not(unix))]
+fn tty_width() -> Option<usize> {
+    None
+}
+
+pub fn strip_ansi(text: &str) -> String {
+    ANSI_PATTERN.replace_all(text, "").to_string()
+}
+
+/// Columns a string occupies on screen: ANSI codes are ignored, wide
+/// (CJK/emoji) characters count as two and combining marks as zero.
+pub fn get_display_width(text: &str) -> usize {
+    strip_ansi(text).chars().map(char_width).sum()
+}
+
+pub(crate) fn char_width(c: char) -> usize {
+    let cp = c as u32;
+    match cp {
+        0 => 0,
+        0x0300..=0x036F | 0x200B..=0x200F | 0xFE00..=0xFE0F => 0,
+        _ if c.is_control() => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
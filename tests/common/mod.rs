@@ -0,0 +1,31 @@
+// Shared helpers for integration tests.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::sync::Once;
+
+static BUILD: Once = Once::new();
+
+/// Path to a compiled example, building all examples on first use.
+pub fn example_bin(name: &str) -> PathBuf {
+    BUILD.call_once(|| {
+        let status = std::process::Command::new(env!("CARGO"))
+            .args(["build", "--quiet", "--examples"])
+            .status()
+            .expect("failed to run cargo build");
+        assert!(status.success(), "building examples failed");
+    });
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target/debug/examples");
+    path.push(name);
+    path
+}
+
+pub fn showcase_bin() -> PathBuf {
+    example_bin("showcase")
+}
+
+/// A fresh temp directory for a test.
+pub fn temp_dir() -> assert_fs::TempDir {
+    assert_fs::TempDir::new().expect("failed to create temp dir")
+}
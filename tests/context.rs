@@ -0,0 +1,252 @@
+use rsb::prelude::*;
+
+#[test]
+fn set_get_unset_var() {
+    set_var("CTX_T1", "value");
+    assert!(has_var("CTX_T1"));
+    assert_eq!(get_var("CTX_T1"), "value");
+    unset_var("CTX_T1");
+    assert!(!has_var("CTX_T1"));
+    assert_eq!(get_var("CTX_T1"), "");
+}
+
+#[test]
+fn expand_vars_handles_both_forms() {
+    set_var("CTX_T2_NAME", "rsb");
+    assert_eq!(expand_vars("hi $CTX_T2_NAME!"), "hi rsb!");
+    assert_eq!(expand_vars("${CTX_T2_NAME}_suffix"), "rsb_suffix");
+    assert_eq!(expand_vars("$CTX_T2_UNSET|"), "|");
+    assert_eq!(expand_vars("cost: $5"), "cost: $5");
+}
+
+#[test]
+fn expand_vars_is_single_pass() {
+    set_var("CTX_T3_A", "$CTX_T3_B");
+    set_var("CTX_T3_B", "hello");
+    assert_eq!(expand_vars("$CTX_T3_A"), "$CTX_T3_B");
+}
+
+#[test]
+fn config_content_sets_vars_and_arrays() {
+    parse_config_content("# comment\nCTX_T4_NAME='quoted value'\nCTX_T4_LIST=(a b c)\n");
+    assert_eq!(get_var("CTX_T4_NAME"), "quoted value");
+    assert_eq!(get_array("CTX_T4_LIST"), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn save_and_load_config_round_trip() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let path = dir.path().join("saved.conf");
+    let path = path.to_str().unwrap();
+
+    set_var("CTX_T5_A", "two words");
+    set_array("CTX_T5_ARR", &["x", "y"]);
+    save_config_file(path, &["CTX_T5_A", "CTX_T5_ARR"]);
+
+    unset_var("CTX_T5_A");
+    load_config_file(path);
+    assert_eq!(get_var("CTX_T5_A"), "two words");
+    assert_eq!(get_array("CTX_T5_ARR"), vec!["x", "y"]);
+}
+
+#[test]
+fn config_search_later_locations_override() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let root = dir.path().to_str().unwrap();
+    let etc = format!("{}/etc", root);
+    let home = format!("{}/home", root);
+    write_file(&format!("{}/ctxsearch.conf", etc), "CTX_CS_A=etc\nCTX_CS_B=etc\n");
+    write_file(&format!("{}/.config/ctxapp/ctxsearch.conf", home), "CTX_CS_B=user\n");
+
+    let loaded = env_guard!({ "XDG_ETC" => etc, "HOME" => home, "SCRIPT_NAME" => "ctxapp" } => {
+        load_config_search!("ctxsearch.conf")
+    });
+    assert_eq!(
+        loaded,
+        vec![
+            format!("{}/ctxsearch.conf", etc),
+            format!("{}/.config/ctxapp/ctxsearch.conf", home)
+        ]
+    );
+    assert_eq!(get_var("CTX_CS_A"), "etc");
+    assert_eq!(get_var("CTX_CS_B"), "user");
+    assert!(load_config_search!("ctx-no-such.conf").is_empty());
+}
+
+#[test]
+fn events_reach_trap_handlers() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let hits = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::clone(&hits);
+    trap!(move |data: &EventData| {
+        assert_eq!(data.data["who"], "test");
+        seen.fetch_add(1, Ordering::SeqCst);
+    }, on: "CTX_T6_EVENT");
+
+    event!(emit: "CTX_T6_EVENT", "who" => "test");
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn escaped_dollar_is_literal() {
+    set_var("CTX_T7_HOME", "/home/rsb");
+    assert_eq!(expand_vars(r"\$CTX_T7_HOME"), "$CTX_T7_HOME");
+    assert_eq!(
+        expand_vars(r"\$CTX_T7_HOME and $CTX_T7_HOME"),
+        "$CTX_T7_HOME and /home/rsb"
+    );
+    assert_eq!(expand_vars(r"$CTX_T7_HOME\$CTX_T7_HOME"), "/home/rsb$CTX_T7_HOME");
+    assert_eq!(expand_vars(r"price: \$5"), "price: $5");
+    assert_eq!(expand_vars("$$"), "$$");
+}
+
+#[test]
+fn recursive_expansion_follows_chains() {
+    set_var("CTX_T8_A", "$CTX_T8_B");
+    set_var("CTX_T8_B", "${CTX_T8_C}!");
+    set_var("CTX_T8_C", "hello");
+    assert_eq!(expand_vars("$CTX_T8_A"), "$CTX_T8_B");
+    assert_eq!(expand_vars_recursive("$CTX_T8_A"), "hello!");
+}
+
+#[test]
+fn recursive_expansion_stops_on_cycles() {
+    set_var("CTX_T9_SELF", "x$CTX_T9_SELF");
+    assert_eq!(expand_vars_recursive("$CTX_T9_SELF"), "x$CTX_T9_SELF");
+
+    set_var("CTX_T9_A", "a$CTX_T9_B");
+    set_var("CTX_T9_B", "b$CTX_T9_A");
+    assert_eq!(expand_vars_recursive("$CTX_T9_A"), "ab$CTX_T9_A");
+}
+
+#[test]
+fn recursive_expansion_caps_depth() {
+    for i in 0..20 {
+        set_var(format!("CTX_T10_{}", i), format!("$CTX_T10_{}", i + 1));
+    }
+    set_var("CTX_T10_20", "end");
+    let out = expand_vars_recursive("$CTX_T10_0");
+    assert_eq!(out, format!("$CTX_T10_{}", MAX_EXPAND_DEPTH));
+}
+
+// The kv store is process-wide, so exercise it from a single test
+#[test]
+fn kv_store_persists_across_reopen() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let path = dir.path().join("state.db");
+    let path = path.to_str().unwrap();
+
+    kv!(open: path);
+    kv!(set: "CTX_KV_RUNS" => "3");
+    kv!(set: "CTX_KV_LAST" => "deploy to prod");
+    kv!(set: "CTX_KV_TEMP" => "x");
+    assert_eq!(kv!(get: "CTX_KV_RUNS"), "3");
+
+    kv!(del: "CTX_KV_TEMP");
+    assert_eq!(kv!(get: "CTX_KV_TEMP"), "");
+
+    // Forget everything in memory, then reopen from disk
+    for key in ["CTX_KV_RUNS", "CTX_KV_LAST", "CTX_KV_TEMP"] {
+        unset_var(key);
+    }
+    kv!(open: path);
+    assert_eq!(kv!(get: "CTX_KV_RUNS"), "3");
+    assert_eq!(kv!(get: "CTX_KV_LAST"), "deploy to prod");
+    assert!(!has_var("CTX_KV_TEMP"));
+    assert!(!read_file(path).contains("CTX_KV_TEMP"));
+}
+
+#[test]
+fn source_env_reaches_child_processes() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let path = dir.path().join(".env");
+    let path = path.to_str().unwrap();
+    write_file(
+        path,
+        "# service settings\nexport CTX_ENV_URL=\"http://localhost:8080\"\nCTX_ENV_NAME='my app'\nCTX_ENV_MODE=dev # inline note\n",
+    );
+
+    source_env!(path);
+    assert_eq!(get_var("CTX_ENV_URL"), "http://localhost:8080");
+    assert_eq!(get_var("CTX_ENV_NAME"), "my app");
+    assert_eq!(get_var("CTX_ENV_MODE"), "dev");
+
+    // Resolved by the child shell from its inherited environment
+    let seen = run!(r#"echo "$CTX_ENV_NAME|$CTX_ENV_MODE""#);
+    assert_eq!(seen, "my app|dev");
+}
+
+#[test]
+fn typed_getters() {
+    set_var("CTX_TY_PORT", " 8080 ");
+    set_var("CTX_TY_BAD", "eighty");
+    assert_eq!(get_var_int("CTX_TY_PORT", 0), 8080);
+    assert_eq!(get_var_int("CTX_TY_BAD", 3), 3);
+    assert_eq!(get_var_int("CTX_TY_UNSET", -1), -1);
+
+    for truthy in ["true", "1", "YES", "On"] {
+        set_var("CTX_TY_FLAG", truthy);
+        assert!(get_var_bool("CTX_TY_FLAG"), "{}", truthy);
+    }
+    for falsy in ["false", "0", "no", "off", "maybe"] {
+        set_var("CTX_TY_FLAG", falsy);
+        assert!(!get_var_bool("CTX_TY_FLAG"), "{}", falsy);
+    }
+    assert!(!get_var_bool("CTX_TY_UNSET"));
+
+    set_var("CTX_TY_WORDS", "a b  c");
+    assert_eq!(get_var_list("CTX_TY_WORDS"), vec!["a", "b", "c"]);
+    set_array("CTX_TY_ARR", &["one item", "two"]);
+    assert_eq!(get_var_list("CTX_TY_ARR"), vec!["one item", "two"]);
+    assert!(get_var_list("CTX_TY_UNSET").is_empty());
+}
+
+#[test]
+fn env_guard_restores_prior_values() {
+    set_var("CTX_EG_LEVEL", "info");
+    unset_var("CTX_EG_NEW");
+
+    let seen = env_guard!({ "CTX_EG_LEVEL" => "debug", "CTX_EG_NEW" => "1" } => {
+        assert_eq!(std::env::var("CTX_EG_NEW").unwrap(), "1");
+        get_var("CTX_EG_LEVEL")
+    });
+    assert_eq!(seen, "debug");
+    assert_eq!(get_var("CTX_EG_LEVEL"), "info");
+    assert!(!has_var("CTX_EG_NEW"));
+    assert!(std::env::var("CTX_EG_NEW").is_err());
+
+    fn early_return() -> i32 {
+        env_guard!({ "CTX_EG_LEVEL" => "trace" } => {
+            if get_var("CTX_EG_LEVEL") == "trace" {
+                return 1;
+            }
+        });
+        0
+    }
+    assert_eq!(early_return(), 1);
+    assert_eq!(get_var("CTX_EG_LEVEL"), "info");
+}
+
+#[test]
+fn on_change_observers_see_old_and_new() {
+    use std::sync::{Arc, Mutex};
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = seen.clone();
+    on_change("OBS_PORT", move |old, new| {
+        sink.lock().unwrap().push(format!("{}->{}", old, new));
+        // Observers may update derived vars
+        set_var("OBS_URL", format!("http://localhost:{}", new));
+    });
+
+    set_var("OBS_PORT", "80");
+    set_var("OBS_PORT", "80"); // unchanged: no callback
+    set_var("OBS_PORT", "8080");
+    set_var("OBS_OTHER", "1");
+    unset_var("OBS_PORT");
+
+    assert_eq!(*seen.lock().unwrap(), vec!["->80", "80->8080", "8080->"]);
+    assert_eq!(get_var("OBS_URL"), "http://localhost:");
+}
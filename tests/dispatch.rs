@@ -0,0 +1,284 @@
+mod common;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use rsb::prelude::*;
+
+fn showcase() -> Command {
+    Command::new(common::showcase_bin())
+}
+
+#[test]
+fn dispatch_routes_to_handler() {
+    showcase()
+        .args(["greet", "rsb"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Hello, rsb!"));
+}
+
+#[test]
+fn quiet_stdout_silences_echo_but_not_errors() {
+    showcase()
+        .args(["greet", "rsb"])
+        .env("RSB_QUIET_STDOUT", "1")
+        .assert()
+        .success()
+        .stdout("");
+    showcase()
+        .arg("fail")
+        .env("RSB_QUIET_STDOUT", "1")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Something went wrong"));
+}
+
+#[test]
+fn dispatch_propagates_handler_exit_code() {
+    showcase()
+        .arg("fail")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Something went wrong"));
+}
+
+#[test]
+fn unknown_command_prints_help_and_fails() {
+    showcase()
+        .arg("nope")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Unknown command: nope"))
+        .stdout(predicate::str::contains("Available commands:"));
+}
+
+#[test]
+fn pre_dispatch_runs_and_exits() {
+    showcase()
+        .arg("install")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Installed"));
+}
+
+#[test]
+fn stream_handler_reads_stdin() {
+    showcase()
+        .arg("stream")
+        .write_stdin("b\na\nb\n")
+        .assert()
+        .success()
+        .stdout("a\nb\n");
+}
+
+#[test]
+fn config_handler_loads_file() {
+    let dir = common::temp_dir();
+    let conf = dir.path().join("app.conf");
+    std::fs::write(&conf, "# settings\nAPP_NAME=\"demo app\"\n").unwrap();
+    showcase()
+        .args(["config", conf.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("demo app\n");
+}
+
+#[test]
+fn default_arm_handles_unregistered_commands() {
+    let bin = common::example_bin("default_handler");
+    Command::new(&bin)
+        .args(["notes.txt", "more.txt"])
+        .assert()
+        .success()
+        .stdout("processing notes.txt\nprocessing more.txt\n");
+
+    Command::new(&bin).arg("version").assert().success().stdout("1.0.0\n");
+
+    // Built-ins still win over the catch-all
+    Command::new(&bin)
+        .arg("help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Available commands:"));
+}
+
+mod in_process {
+    use rsb::prelude::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static INSTALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn do_install(args: Args) -> i32 {
+        assert_eq!(args.get(1), "--force");
+        INSTALLS.fetch_add(1, Ordering::SeqCst);
+        0
+    }
+
+    fn argv(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn pre_dispatch_returns_whether_it_handled() {
+        let handled = pre_dispatch!(&argv(&["tool", "install", "--force"]), {
+            "install" => do_install
+        });
+        assert!(handled);
+        assert_eq!(INSTALLS.load(Ordering::SeqCst), 1);
+
+        let handled = pre_dispatch!(&argv(&["tool", "build"]), { "install" => do_install });
+        assert!(!handled);
+        assert!(!pre_dispatch!(&argv(&["tool"]), { "install" => do_install }));
+        assert_eq!(INSTALLS.load(Ordering::SeqCst), 1);
+    }
+}
+
+#[test]
+fn stream_inspect_logs_only_in_debug_mode() {
+    showcase()
+        .arg("stream")
+        .env("DEBUG", "1")
+        .write_stdin("b\na\nb\n")
+        .assert()
+        .success()
+        .stdout("a\nb\n")
+        .stderr(predicate::str::contains("input: 3 lines"))
+        .stderr(predicate::str::contains("sorted: 2 lines"));
+
+    showcase()
+        .arg("stream")
+        .env_remove("DEBUG")
+        .env_remove("TRACE")
+        .write_stdin("b\na\n")
+        .assert()
+        .success()
+        .stderr("");
+}
+
+#[test]
+fn help_for_a_single_command() {
+    showcase()
+        .args(["help", "greet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Usage:"))
+        .stdout(predicate::str::contains("greet [name]"))
+        .stdout(predicate::str::contains("Available commands:").not());
+
+    // Unknown topics fall back to the general help
+    showcase()
+        .args(["help", "nope"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Available commands:"));
+}
+
+fn in_process_greet(args: Args) -> i32 {
+    echo!("hi {}", args.get_or(1, "there"));
+    info!("greeted");
+    0
+}
+
+fn in_process_fail(_args: Args) -> i32 {
+    error!("nope");
+    4
+}
+
+#[test]
+fn testing_harness_runs_handlers_in_process() {
+    let route = |argv: &[String]| try_dispatch!(argv, { "greet" => in_process_greet, "fail" => in_process_fail });
+
+    let run = rsb::testing::run(&["greet", "bob"], route);
+    assert_eq!(run.code, 0);
+    assert_eq!(run.stdout, "hi bob\n");
+    assert!(run.stderr.contains("greeted"));
+
+    let run = rsb::testing::run(&["fail"], route);
+    assert_eq!(run.code, 4);
+    assert!(run.stdout.is_empty());
+    assert!(run.stderr.contains("nope"));
+
+    let run = rsb::testing::run(&["bogus"], route);
+    assert_eq!(run.code, 1);
+    assert!(run.stderr.contains("Unknown command: bogus"));
+    assert!(run.stdout.contains("Available commands:"));
+}
+
+fn echo_args(args: Args) -> i32 {
+    echo!(args.all().join(","));
+    0
+}
+
+fn set_disp_profile(value: &str) {
+    set_var("DISP_GLOBAL_PROFILE", value);
+}
+
+#[test]
+fn global_options_apply_before_routing() {
+    let route = |argv: &[String]| {
+        try_dispatch!(argv, global: {
+            "--verbose" => set DEBUG_MODE,
+            "--profile" => set_disp_profile
+        }, { "show" => echo_args })
+    };
+
+    let run = rsb::testing::run(&["--verbose", "show", "a", "--profile", "prod", "b"], route);
+    assert_eq!(run.code, 0);
+    assert_eq!(run.stdout, "a,b\n");
+    assert_eq!(get_var("DEBUG_MODE"), "1");
+    assert_eq!(get_var("DISP_GLOBAL_PROFILE"), "prod");
+    unset_var("DEBUG_MODE");
+
+    // `flag=value` works too, and nothing after `--` is touched
+    let run = rsb::testing::run(&["show", "--profile=dev", "--", "--verbose"], route);
+    assert_eq!(run.stdout, "--,--verbose\n");
+    assert_eq!(get_var("DISP_GLOBAL_PROFILE"), "dev");
+    assert!(!has_var("DEBUG_MODE"));
+}
+
+fn traced_handler(_args: Args) -> i32 {
+    7
+}
+
+#[test]
+fn command_events_wrap_handlers() {
+    use std::sync::{Arc, Mutex};
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = seen.clone();
+    trap!(move |e: &EventData| {
+        if e.data.get("command").map(String::as_str) == Some("traced") {
+            let code = e.data.get("code").cloned().unwrap_or_default();
+            sink.lock().unwrap().push(format!("{}:{}", e.event_type, code));
+        }
+    }, on: "command_start");
+    let sink = seen.clone();
+    trap!(move |e: &EventData| {
+        if e.data.get("command").map(String::as_str) == Some("traced") {
+            let code = e.data.get("code").cloned().unwrap_or_default();
+            sink.lock().unwrap().push(format!("{}:{}", e.event_type, code));
+            assert!(e.data.contains_key("duration_ms"));
+        }
+    }, on: "command_end");
+
+    let run = rsb::testing::run(&["traced", "x"], |argv| try_dispatch!(argv, { "traced" => traced_handler }));
+    assert_eq!(run.code, 7);
+    assert_eq!(*seen.lock().unwrap(), vec!["command_start:", "command_end:7"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_replaces_the_process() {
+    showcase().args(["exec", "true"]).assert().code(0);
+    showcase().args(["exec", "false"]).assert().code(1);
+    showcase()
+        .args(["exec", "sh", "-c", "echo replaced; exit 7"])
+        .assert()
+        .code(7)
+        .stdout("replaced\n");
+    showcase()
+        .args(["exec", "/no/such/program"])
+        .assert()
+        .code(127)
+        .stderr(predicate::str::contains("exec failed"));
+}
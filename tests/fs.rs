@@ -0,0 +1,296 @@
+use rsb::prelude::*;
+
+#[test]
+fn write_file_creates_parent_dirs() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let path = format!("{}/build/logs/build.log", dir.path().to_str().unwrap());
+    write_file(&path, "started\n");
+    assert_eq!(read_file(&path), "started\n");
+}
+
+#[test]
+fn grep_recursive_finds_lines() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let base = dir.path().to_str().unwrap().to_string();
+    write_file(&format!("{}/src/main.rs", base), "fn main() {\n    todo!()\n}\n");
+    write_file(&format!("{}/src/deep/lib.rs", base), "// TODO: docs\npub fn x() {}\n");
+    write_file(&format!("{}/notes.md", base), "todo: ship it\n");
+
+    let hits = grep_recursive(&base, "(?i)todo", "*.rs");
+    assert_eq!(
+        hits,
+        vec![
+            (format!("{}/src/deep/lib.rs", base), 1, "// TODO: docs".to_string()),
+            (format!("{}/src/main.rs", base), 2, "    todo!()".to_string()),
+        ]
+    );
+    assert_eq!(grep_recursive(&base, "ship", "").len(), 1);
+    assert!(grep_recursive(&base, "nothing here", "").is_empty());
+}
+
+#[test]
+fn binary_files_round_trip_through_base64_streams() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let base = dir.path().to_str().unwrap();
+    let original = format!("{}/blob.bin", base);
+    let bytes: Vec<u8> = (0..=255u8).chain([0, b'\n', b'\r', 0xff, 0xfe]).cycle().take(1000).collect();
+    std::fs::write(&original, &bytes).unwrap();
+
+    let encoded = encode_file_base64(&original);
+    assert!(encoded.lines().all(|line| line.len() <= 76));
+
+    let copy = format!("{}/out/blob.bin", base);
+    Stream::from_file_base64(&original)
+        .inspect("b64")
+        .decode_base64_to_file(&copy)
+        .unwrap();
+    assert_eq!(std::fs::read(&copy).unwrap(), bytes);
+
+    assert!(decode_base64_to_file("not base64!", &copy).is_err());
+    assert_eq!(std::fs::read(&copy).unwrap(), bytes);
+}
+
+#[test]
+fn write_read_append() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let path = dir.path().join("file.txt");
+    let path = path.to_str().unwrap();
+
+    write_file(path, "a\n");
+    append_file(path, "b\n");
+    assert_eq!(read_file(path), "a\nb\n");
+    assert!(test!(-f path));
+    assert!(test!(-s path));
+}
+
+#[test]
+fn copy_move_remove() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let base = dir.path().to_str().unwrap().to_string();
+    let src = format!("{}/tree/src.txt", base);
+
+    mkdir_p(&format!("{}/tree", base));
+    write_file(&src, "data");
+    cp_r(&format!("{}/tree", base), &format!("{}/copy", base));
+    assert_eq!(read_file(&format!("{}/copy/src.txt", base)), "data");
+
+    mv(&src, &format!("{}/tree/moved.txt", base));
+    assert!(!is_file(&src));
+
+    rm_rf(&format!("{}/tree", base));
+    assert!(!is_dir(&format!("{}/tree", base)));
+}
+
+#[cfg(unix)]
+#[test]
+fn chmod_octal() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = assert_fs::TempDir::new().unwrap();
+    let path = dir.path().join("script.sh");
+    let path = path.to_str().unwrap();
+    touch(path);
+    chmod!(path, "750");
+    let mode = std::fs::metadata(path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o750);
+}
+
+#[test]
+fn backup_and_meta() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let path = dir.path().join("tool.sh");
+    let path = path.to_str().unwrap();
+    write_file(path, "#!/bin/sh\n# @name: tool\n# @version: 1.2\necho hi\n");
+
+    let backup = backup!(path);
+    assert_eq!(read_file(&backup), read_file(path));
+    assert_eq!(meta_key!(path, "version"), "1.2");
+    assert_eq!(meta_keys!(path).len(), 2);
+}
+
+#[test]
+fn sed_insert_requires_unique_sentinel() {
+    assert_eq!(sed_insert!("X", "{{S}}", "a {{S}} b").unwrap(), "a X b");
+    assert!(sed_insert!("X", "{{S}}", "{{S}}{{S}}").is_err());
+    assert_eq!(sed_template!("X", "{{S}}", "{{S}}{{S}}"), "XX");
+}
+
+#[test]
+fn temp_files_capture_streams() {
+    let path = cap_stream!(pipe!("captured"));
+    assert_eq!(read_file(&path), "captured");
+}
+
+#[test]
+fn path_split_sets_parts() {
+    path_split!("/opt/app/config.toml", into: "FS_P");
+    assert_eq!(get_var("FS_P_parent"), "/opt/app");
+    assert_eq!(get_var("FS_P_file_stem"), "config");
+    assert_eq!(get_var("FS_P_extension"), "toml");
+}
+
+#[test]
+fn template_renders_context_vars() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let tpl = dir.path().join("app.tpl");
+    let out = dir.path().join("app.conf");
+    let (tpl, out) = (tpl.to_str().unwrap(), out.to_str().unwrap());
+    write_file(tpl, "version=$FS_TPL_VERSION\nenv=${FS_TPL_ENV}\n");
+
+    set_var("FS_TPL_VERSION", "1.4.0");
+    set_var("FS_TPL_ENV", "prod");
+
+    let preview = template!(from: tpl, to: out, dry_run);
+    assert_eq!(preview, "version=1.4.0\nenv=prod\n");
+    assert!(!is_file(out));
+
+    template!(from: tpl, to: out);
+    assert_eq!(read_file(out), preview);
+}
+
+#[cfg(unix)]
+#[test]
+fn chmod_symbolic() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = assert_fs::TempDir::new().unwrap();
+    let path = dir.path().join("tool");
+    let path = path.to_str().unwrap();
+    let mode = || std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+    touch(path);
+
+    chmod!(path, "666");
+    chmod!(path, "+x");
+    assert_eq!(mode(), 0o777);
+
+    chmod!(path, "go-w");
+    assert_eq!(mode(), 0o755);
+
+    chmod!(path, "u-x,o=r");
+    assert_eq!(mode(), 0o654);
+
+    chmod!(path, "bogus");
+    assert_eq!(mode(), 0o654);
+}
+
+#[cfg(unix)]
+#[test]
+fn chmod_and_chown_recursive() {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+    let dir = assert_fs::TempDir::new().unwrap();
+    let root = format!("{}/tree", dir.path().to_str().unwrap());
+    let nested = format!("{}/a/b/deep.txt", root);
+    write_file(&nested, "x");
+    chmod!(nested, "600");
+    let mode = |p: &str| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+
+    chmod!(root, "go+r", recursive);
+    assert_eq!(mode(&nested), 0o644);
+    chmod!(root, "700", recursive);
+    assert_eq!(mode(&nested), 0o700);
+    assert_eq!(mode(&format!("{}/a/b", root)), 0o700);
+
+    // Re-owning to ourselves needs no privileges and exercises name lookup
+    let uid = std::fs::metadata(&nested).unwrap().uid();
+    let gid = std::fs::metadata(&nested).unwrap().gid();
+    chown!(root, user!(), gid.to_string(), recursive);
+    chown!(nested, uid.to_string(), "");
+    assert_eq!(std::fs::metadata(&nested).unwrap().uid(), uid);
+    assert_eq!(std::fs::metadata(&nested).unwrap().gid(), gid);
+    chown!(nested, "no-such-user-rsb", "");
+    assert_eq!(std::fs::metadata(&nested).unwrap().uid(), uid);
+}
+
+#[test]
+fn backup_rotation_shifts_and_prunes() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let path = dir.path().join("app.conf");
+    let path = path.to_str().unwrap();
+
+    for version in 1..=4 {
+        write_file(path, &format!("v{}", version));
+        assert_eq!(backup!(path, keep: 3), format!("{}.1", path));
+    }
+
+    assert_eq!(read_file(&format!("{}.1", path)), "v4");
+    assert_eq!(read_file(&format!("{}.2", path)), "v3");
+    assert_eq!(read_file(&format!("{}.3", path)), "v2");
+    assert!(!is_file(&format!("{}.4", path)));
+
+    // Shrinking keep prunes the tail
+    backup!(path, keep: 2);
+    assert!(is_file(&format!("{}.2", path)));
+    assert!(!is_file(&format!("{}.3", path)));
+}
+
+#[test]
+fn backup_timestamped_names() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let path = dir.path().join("data.db");
+    let path = path.to_str().unwrap();
+    write_file(path, "rows");
+
+    let backup = backup!(path, timestamp);
+    let suffix = backup.strip_prefix(&format!("{}.", path)).unwrap();
+    assert!(test!(suffix, =~, r"^\d{8}-\d{6}$"));
+    assert_eq!(read_file(&backup), "rows");
+}
+
+#[test]
+fn meta_set_inserts_and_updates_headers() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let path = dir.path().join("tool.sh");
+    let path = path.to_str().unwrap();
+    write_file(path, "#!/bin/sh\n# @name: tool\necho hi\n");
+
+    meta_set!(path, "version", "0.1");
+    meta_set!(path, "name", "renamed");
+
+    let meta = extract_meta_from_file(path);
+    assert_eq!(meta["name"], "renamed");
+    assert_eq!(meta["version"], "0.1");
+    assert_eq!(
+        read_file(path),
+        "#!/bin/sh\n# @name: renamed\n# @version: 0.1\necho hi\n"
+    );
+
+    let bare = dir.path().join("notes.txt");
+    let bare = bare.to_str().unwrap();
+    write_file(bare, "body");
+    meta_set!(bare, "author", "rsb");
+    assert_eq!(read_file(bare), "# @author: rsb\nbody");
+}
+
+#[cfg(feature = "watch")]
+#[test]
+fn watch_debounces_a_burst_into_one_rerun() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let dir = assert_fs::TempDir::new().unwrap();
+    let base = dir.path().to_str().unwrap().to_string();
+    let target = format!("{}/src/main.rs", base);
+    mkdir_p!(format!("{}/src", base));
+    write_file(&target, "fn main() {}\n");
+
+    let runs = Arc::new(AtomicUsize::new(0));
+    let counter = runs.clone();
+    let pattern = format!("{}/**/*.rs", base);
+    let watcher = std::thread::spawn(move || {
+        watch!(pattern, debounce: 300, runs: 2 => {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+    });
+    std::thread::sleep(std::time::Duration::from_millis(300));
+
+    // An editor-style burst: write, then save via temp file + rename
+    write_file(&target, "fn main() { 1; }\n");
+    write_file(&format!("{}/src/.main.rs.swp", base), "tmp\n");
+    std::fs::rename(format!("{}/src/.main.rs.swp", base), &target).unwrap();
+    write_file(&format!("{}/notes.txt", base), "ignored\n");
+    std::thread::sleep(std::time::Duration::from_millis(1200));
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    // The replaced file is still watched
+    write_file(&target, "fn main() { 2; }\n");
+    assert_eq!(watcher.join().unwrap().unwrap(), 2);
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use assert_cmd::Command;
+use std::time::Duration;
+
+fn confirm(default: &str) -> Command {
+    let mut cmd = Command::new(common::showcase_bin());
+    cmd.args(["confirm", default])
+        .env_remove("RSB_ASSUME_YES")
+        .timeout(Duration::from_secs(5));
+    cmd
+}
+
+#[test]
+fn closed_stdin_returns_default() {
+    confirm("yes").write_stdin("").assert().success().stdout("yes\n");
+    confirm("no").write_stdin("").assert().success().stdout("no\n");
+}
+
+#[test]
+fn closed_stdin_without_default_is_no() {
+    confirm("").write_stdin("").assert().success().stdout("no\n");
+}
+
+#[test]
+fn garbage_input_without_tty_does_not_loop() {
+    confirm("").write_stdin("maybe\n\n").assert().success().stdout("no\n");
+}
+
+#[test]
+fn piped_answer_is_honoured() {
+    confirm("no").write_stdin("y\n").assert().success().stdout("yes\n");
+}
+
+#[test]
+fn assume_yes_skips_the_prompt() {
+    confirm("no")
+        .env("RSB_ASSUME_YES", "1")
+        .write_stdin("n\n")
+        .assert()
+        .success()
+        .stdout("yes\n");
+    confirm("no").arg("--yes").write_stdin("").assert().success().stdout("yes\n");
+}
+
+#[test]
+fn destructive_confirm_needs_the_exact_word() {
+    let wipe = |word: &str| {
+        let mut cmd = Command::new(common::showcase_bin());
+        cmd.args(["wipe", word])
+            .env_remove("RSB_ASSUME_YES")
+            .timeout(Duration::from_secs(5));
+        cmd
+    };
+    wipe("").write_stdin("y\n").assert().success().stdout("kept\n");
+    wipe("").write_stdin("yes\n").assert().success().stdout("wiped\n");
+    wipe("delete-prod").write_stdin("yes\n").assert().success().stdout("kept\n");
+    wipe("delete-prod").write_stdin("delete-prod\n").assert().success().stdout("wiped\n");
+    wipe("").write_stdin("").assert().success().stdout("kept\n");
+
+    // --yes doesn't count; only --force skips the prompt
+    wipe("").env("RSB_ASSUME_YES", "1").write_stdin("").assert().success().stdout("kept\n");
+    wipe("").arg("--force").write_stdin("").assert().success().stdout("wiped\n");
+}
+
+#[test]
+fn timed_readline_returns_quick_input() {
+    Command::new(common::showcase_bin())
+        .args(["ask", "5"])
+        .write_stdin("n\n")
+        .timeout(Duration::from_secs(5))
+        .assert()
+        .success()
+        .stdout("n\n");
+}
+
+#[test]
+fn timed_readline_falls_back_to_default() {
+    // Keep stdin open but silent so only the deadline can end the read
+    let mut child = std::process::Command::new(common::showcase_bin())
+        .args(["ask", "1"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let _stdin = child.stdin.take();
+    let start = std::time::Instant::now();
+    let output = child.wait_with_output().unwrap();
+    assert!(start.elapsed() < Duration::from_secs(4));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "y\n");
+}
+
+#[test]
+fn confirm_timeout_takes_a_quick_answer() {
+    confirm("yes").arg("5").write_stdin("n\n").assert().success().stdout("no\n");
+    confirm("no").arg("5").write_stdin("y\n").assert().success().stdout("yes\n");
+}
+
+#[test]
+fn confirm_timeout_defaults_when_time_runs_out() {
+    let mut child = std::process::Command::new(common::showcase_bin())
+        .args(["confirm", "yes", "1"])
+        .env_remove("RSB_ASSUME_YES")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    let _stdin = child.stdin.take();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "yes\n");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("(1s)"));
+}
+
+#[test]
+fn confirm_timeout_skips_under_assume_yes() {
+    confirm("no").arg("30").env("RSB_ASSUME_YES", "1").write_stdin("").assert().success().stdout("no\n");
+}
+
+#[test]
+fn multiline_prompt_reads_until_sentinel() {
+    Command::new(common::showcase_bin())
+        .arg("note")
+        .env("EDITOR", "false")
+        .write_stdin("first line\n\nthird\n.\nnot read\n")
+        .timeout(Duration::from_secs(5))
+        .assert()
+        .success()
+        .stdout("3 lines\nfirst line\n\nthird\n");
+
+    Command::new(common::showcase_bin())
+        .args(["note", "EOF"])
+        .write_stdin("a\n.\nEOF\n")
+        .timeout(Duration::from_secs(5))
+        .assert()
+        .success()
+        .stdout("2 lines\na\n.\n");
+
+    // EOF ends input too
+    Command::new(common::showcase_bin())
+        .arg("note")
+        .write_stdin("only")
+        .timeout(Duration::from_secs(5))
+        .assert()
+        .success()
+        .stdout("1 lines\nonly\n");
+}
@@ -0,0 +1,404 @@
+mod common;
+
+use rsb::prelude::*;
+
+#[test]
+fn run_and_shell() {
+    assert_eq!(run!("echo hello"), "hello");
+    assert_eq!(run!("exit 3", silent), "");
+    let result = shell!("echo out; echo err >&2; exit 2");
+    assert_eq!(result.status, 2);
+    assert_eq!(result.output, "out");
+    assert_eq!(result.error, "err");
+}
+
+#[test]
+fn command_detection() {
+    assert!(is_command("sh"));
+    assert!(!is_command("definitely-not-a-command-rsb"));
+}
+
+#[test]
+fn system_info_is_populated() {
+    assert!(!hostname!().is_empty());
+    assert!(!get_os().is_empty());
+    assert!(!current_dir!().is_empty());
+}
+
+#[test]
+fn streaming_command_sees_each_line() {
+    let mut lines = Vec::new();
+    let code = run_cmd_streaming("printf 'a\\nb\\n'", |l| lines.push(l.to_string()));
+    assert_eq!(code, 0);
+    assert_eq!(lines, vec!["a", "b"]);
+}
+
+#[test]
+fn spinner_run_returns_clean_output() {
+    let out = run!("sleep 0.2; printf 'built\\nok\\n'", spinner: "Building...");
+    assert_eq!(out, "built\nok");
+    assert!(!out.contains('\r') && !out.contains('\x1b'));
+
+    // A failing command still stops the spinner and reports the status
+    let err = rsb::os::try_run_cmd_spinner("echo partial; exit 3", "Failing...").unwrap_err();
+    assert_eq!(err.status(), 3);
+}
+
+#[test]
+fn open_picks_the_platform_launcher() {
+    let url = "https://example.com/a b";
+    assert_eq!(rsb::os::open_command(url, "linux"), ("xdg-open".to_string(), vec![url.to_string()]));
+    assert_eq!(rsb::os::open_command(url, "freebsd").0, "xdg-open");
+    assert_eq!(rsb::os::open_command(url, "macos"), ("open".to_string(), vec![url.to_string()]));
+    let (program, args) = rsb::os::open_command(url, "windows");
+    assert_eq!(program, "cmd");
+    assert_eq!(args, vec!["/C", "start", "", url]);
+}
+
+#[test]
+fn pforeach_runs_on_a_bounded_pool() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    set_array("POOL_HOSTS", &["a", "bb", "ccc", "dddd", "eeeee", "ffffff", "g"]);
+    let active = AtomicUsize::new(0);
+    let peak = AtomicUsize::new(0);
+    let lengths = pforeach!(host in "POOL_HOSTS", threads: 3 => {
+        let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+        peak.fetch_max(now, Ordering::SeqCst);
+        std::thread::sleep(std::time::Duration::from_millis(30));
+        active.fetch_sub(1, Ordering::SeqCst);
+        host.len()
+    });
+    assert_eq!(lengths, vec![1, 2, 3, 4, 5, 6, 1]);
+    assert_eq!(lengths.iter().sum::<usize>(), 22);
+    assert!(peak.load(Ordering::SeqCst) <= 3);
+    assert!(peak.load(Ordering::SeqCst) > 1);
+}
+
+#[test]
+fn cpu_count_sizes_the_default_pool() {
+    let cores = cpu_count!();
+    assert!(cores >= 1);
+    assert_eq!(cores, cpu_count());
+
+    set_array("POOL_DEFAULT", &["x", "yy", "zzz"]);
+    assert_eq!(pforeach!(item in "POOL_DEFAULT" => { item.len() }), vec![1, 2, 3]);
+    assert_eq!(worker_pool(vec!["a".to_string()], 0, |s| s + "!"), vec!["a!"]);
+}
+
+#[test]
+fn temp_with_content_feeds_file_arguments() {
+    let path = temp_with_content("beta\nalpha\ngamma\n");
+    assert!(is_file(&path));
+    assert_eq!(run_cmd(&format!("sort {}", sh_quote(&path))), "alpha\nbeta\ngamma");
+    assert_eq!(run_cmd(&format!("wc -l < {}", sh_quote(&path))).trim(), "3");
+
+    let other = subst!(content: "beta\nalpha\ndelta\n");
+    let common = run_cmd(&format!("cat {} {} | sort | uniq -d", sh_quote(&path), sh_quote(&other)));
+    assert_eq!(common, "alpha\nbeta");
+}
+
+// Serves one canned response per connection, counting requests
+fn mock_http(responses: &[(u16, &str)]) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+    use std::io::{Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let hits = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let counter = hits.clone();
+    let responses: Vec<(u16, String)> = responses.iter().map(|(c, b)| (*c, b.to_string())).collect();
+    std::thread::spawn(move || {
+        for (code, body) in responses {
+            let Ok((mut conn, _)) = listener.accept() else { return };
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                match conn.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let reply = format!(
+                "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                code,
+                body.len(),
+                body
+            );
+            conn.write_all(reply.as_bytes()).ok();
+        }
+    });
+    (url, hits)
+}
+
+#[test]
+fn retry_http_retries_server_errors_only() {
+    use std::sync::atomic::Ordering;
+
+    let (url, hits) = mock_http(&[(500, "oops"), (503, "busy"), (200, "done")]);
+    assert_eq!(retry_http!(url, attempts: 3, delay: 10).unwrap(), "done");
+    assert_eq!(hits.load(Ordering::SeqCst), 3);
+
+    let (url, hits) = mock_http(&[(404, "missing"), (200, "never")]);
+    let err = retry_http!(url, attempts: 3, delay: 10).unwrap_err();
+    assert!(matches!(err, RsbError::Http { status: 404, .. }));
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    let (url, _) = mock_http(&[(201, "line one\nline two")]);
+    let response = http_request_status("GET", &url, None, "");
+    assert_eq!(response.status, 201);
+    assert_eq!(response.body, "line one\nline two");
+}
+
+#[cfg(unix)]
+#[test]
+fn send_signal_by_name() {
+    let me = std::process::id() as i32;
+    assert!(signal!(me, "0").is_ok());
+    assert_eq!(signal_number("hup"), signal_number("SIGHUP"));
+    assert_eq!(signal_number("15"), signal_number("TERM"));
+
+    let err = signal!(me, "BOGUS").unwrap_err();
+    assert_eq!(err.to_string(), format!("Cannot signal {}: unknown signal 'BOGUS'", me));
+    assert!(signal!(i32::MAX, 0.to_string()).is_err());
+
+    let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+    signal!(child.id() as i32, "TERM").unwrap();
+    let status = child.wait().unwrap();
+    use std::os::unix::process::ExitStatusExt;
+    assert_eq!(status.signal(), signal_number("TERM"));
+}
+
+#[test]
+fn run_pipeline_chains_stages() {
+    let result = run_pipeline(&["echo hi", "tr a-z A-Z"]);
+    assert_eq!(result.status, 0);
+    assert_eq!(result.output, "HI");
+
+    let result = run_pipeline(&["printf 'b\\na\\nb\\n'", "sort", "uniq -c", "wc -l"]);
+    assert_eq!(result.output.trim(), "2");
+
+    // pipefail: a failing middle stage sets the status, stderr is kept
+    let result = run_pipeline(&["echo x", "cat; echo oops >&2; exit 3", "cat"]);
+    assert_eq!(result.status, 3);
+    assert_eq!(result.output, "x");
+    assert_eq!(result.error, "oops");
+
+    assert_eq!(run_pipeline(&[]).status, 0);
+}
+
+#[test]
+fn dry_run_logs_instead_of_acting() {
+    use assert_cmd::Command;
+    use predicates::prelude::*;
+
+    let dir = assert_fs::TempDir::new().unwrap();
+    let base = dir.path().to_str().unwrap();
+    let deploy = || {
+        let mut cmd = Command::new(common::showcase_bin());
+        cmd.args(["deploy", base]).env_remove("RSB_DRY_RUN");
+        cmd
+    };
+
+    deploy()
+        .env("RSB_DRY_RUN", "1")
+        .assert()
+        .success()
+        .stdout("deployed\n")
+        .stderr(predicate::str::contains(format!("would run: touch {}/ran", base)))
+        .stderr(predicate::str::contains(format!("would write: {}/written", base)))
+        .stderr(predicate::str::contains("would cp: "));
+    assert!(!is_file(&format!("{}/ran", base)));
+    assert!(!is_file(&format!("{}/written", base)));
+
+    deploy().arg("--dry-run").assert().success();
+    assert!(!is_file(&format!("{}/ran", base)));
+
+    deploy().assert().success().stderr(predicate::str::contains("would").not());
+    assert!(is_file(&format!("{}/ran", base)));
+    assert!(is_file(&format!("{}/copied", base)));
+}
+
+#[test]
+fn fqdn_and_local_ips() {
+    let fqdn = fqdn!();
+    assert!(!fqdn.is_empty());
+    assert!(fqdn.starts_with(&hostname!()) || fqdn.contains('.'));
+
+    let ips = local_ips!();
+    assert!(!ips.is_empty());
+    for ip in &ips {
+        assert!(ip.parse::<std::net::IpAddr>().is_ok(), "not an address: {}", ip);
+    }
+    let all_loopback = ips.iter().all(|ip| ip.parse::<std::net::IpAddr>().unwrap().is_loopback());
+    let any_loopback = ips.iter().any(|ip| ip.parse::<std::net::IpAddr>().unwrap().is_loopback());
+    assert!(all_loopback || !any_loopback, "loopback mixed into {:?}", ips);
+}
+
+#[test]
+fn sh_quote_survives_the_shell() {
+    assert_eq!(sh_quote!("plain"), "'plain'");
+    assert_eq!(sh_quote!("O'Brien.txt"), "'O'\\''Brien.txt'");
+    for value in ["O'Brien.txt", "two words", "$HOME and `id`", "it's $(rm -rf x)", ""] {
+        let echoed = run_cmd_with_status(&format!("printf %s {}", sh_quote!(value)));
+        assert_eq!(echoed.output, value);
+    }
+}
+
+#[test]
+fn archives_handle_quotes_in_paths() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let base = dir.path().to_str().unwrap().to_string();
+    let file = format!("{}/O'Brien notes.txt", base);
+    write_file(&file, "hi\n");
+    let archive = format!("{}/it's.tar", base);
+    rsb::os::try_create_tar(&archive, &[&file]).unwrap();
+    let listing = rsb::os::list_tar(&archive);
+    assert_eq!(listing.status, 0);
+    assert!(listing.output.contains("O'Brien notes.txt"));
+}
+
+#[test]
+fn jobs_wait_and_time_out() {
+    let quick = job!(background: "exit 4");
+    assert_eq!(job!(wait: quick), Some(4));
+
+    let slow = job!(background: "sleep 5");
+    assert_eq!(job!(timeout: 1, wait: slow), None);
+}
+
+#[test]
+fn tar_round_trip() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let base = dir.path().to_str().unwrap().to_string();
+    write_file(&format!("{}/a.txt", base), "A");
+
+    let archive = format!("{}/out.tar.gz", base);
+    let result = create_tar_gz(&archive, &[&format!("{}/a.txt", base)]);
+    assert_eq!(result.status, 0);
+    assert!(list_tar(&archive).output.contains("a.txt"));
+}
+
+#[cfg(unix)]
+#[test]
+fn pids_of_returns_every_match() {
+    if !is_command("pgrep") || !is_command("sleep") {
+        return;
+    }
+    // A uniquely named copy of sleep so pgrep only sees our children
+    let dir = assert_fs::TempDir::new().unwrap();
+    let name = format!("rsbslp{}", rand_hex!(6));
+    let bin = dir.path().join(&name);
+    let sleep_path = run!("command -v sleep");
+    std::fs::copy(&sleep_path, &bin).unwrap();
+
+    let mut children: Vec<_> = (0..3)
+        .map(|_| std::process::Command::new(&bin).arg("30").spawn().unwrap())
+        .collect();
+    let mut expected: Vec<String> = children.iter().map(|c| c.id().to_string()).collect();
+
+    let mut found = pids_of!(&name);
+    found.sort();
+    expected.sort();
+    assert_eq!(found, expected);
+    assert!(expected.contains(&pid_of!(&name)));
+
+    for child in &mut children {
+        child.kill().ok();
+        child.wait().ok();
+    }
+    assert!(pids_of!(&name).is_empty());
+}
+
+#[test]
+fn process_exists_by_pid_natively() {
+    assert!(process_exists_by_pid(&std::process::id().to_string()));
+    assert!(!process_exists_by_pid("999999999"));
+    assert!(!process_exists_by_pid("not-a-pid"));
+}
+
+#[cfg(unix)]
+#[test]
+fn kill_pid_signals_natively() {
+    let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+    let pid = child.id().to_string();
+    assert!(process_exists_by_pid(&pid));
+
+    assert_eq!(kill_pid!(pid, signal: "KILL").status, 0);
+    child.wait().unwrap();
+    assert!(!process_exists_by_pid(&pid));
+    assert_ne!(kill_pid!("999999999").status, 0);
+}
+
+#[test]
+fn parses_os_release_content() {
+    let sample = r#"
+NAME="Ubuntu"
+VERSION_ID="22.04"
+ID=ubuntu
+ID_LIKE=debian
+PRETTY_NAME="Ubuntu 22.04.4 LTS"
+"#;
+    let release = parse_os_release(sample);
+    assert_eq!(
+        release,
+        OsRelease {
+            id: "ubuntu".to_string(),
+            version_id: "22.04".to_string(),
+            pretty_name: "Ubuntu 22.04.4 LTS".to_string(),
+        }
+    );
+    assert_eq!(parse_os_release(""), OsRelease::default());
+}
+
+#[test]
+fn os_release_has_an_id() {
+    assert!(!os_release!().id.is_empty());
+}
+
+#[test]
+fn resource_info_is_populated() {
+    let mem = mem!();
+    assert!(mem.total > 0);
+    assert!(mem.available <= mem.total);
+
+    let (one, five, fifteen) = loadavg!();
+    assert!(one >= 0.0 && five >= 0.0 && fifteen >= 0.0);
+
+    assert!(uptime!().as_secs() > 0);
+}
+
+#[test]
+fn try_variants_return_errors_instead_of_exiting() {
+    match try_run_cmd("echo oops >&2; exit 7") {
+        Err(RsbError::Command { status, stderr, .. }) => {
+            assert_eq!(status, 7);
+            assert_eq!(stderr, "oops");
+        }
+        other => panic!("expected a command error, got {:?}", other),
+    }
+    assert_eq!(try_run_cmd("echo fine").unwrap(), "fine");
+
+    let dir = assert_fs::TempDir::new().unwrap();
+    let lock = dir.path().join("job.lock");
+    let lock = lock.to_str().unwrap();
+    try_create_lock(lock).unwrap();
+    let err = try_create_lock(lock).unwrap_err();
+    assert!(matches!(err, RsbError::LockHeld { .. }));
+    assert!(err.to_string().contains(&std::process::id().to_string()));
+    release_lock(lock);
+
+    let missing = dir.path().join("nope/job.lock");
+    assert!(matches!(
+        try_create_lock(missing.to_str().unwrap()),
+        Err(RsbError::Io { .. })
+    ));
+
+    let archive = dir.path().join("missing.tar");
+    let err = try_extract_tar(archive.to_str().unwrap(), None).unwrap_err();
+    assert_ne!(err.status(), 0);
+    assert!(try_create_tar(
+        dir.path().join("out.tar").to_str().unwrap(),
+        &[dir.path().join("absent").to_str().unwrap()]
+    )
+    .is_err());
+}
@@ -0,0 +1,60 @@
+use rsb::prelude::*;
+
+#[test]
+fn random_values_have_requested_shape() {
+    assert_eq!(rand_alnum!(12).len(), 12);
+    assert!(rand_hex!(8).chars().all(|c| c.is_ascii_hexdigit()));
+    assert!(rand_alpha!(8).chars().all(|c| c.is_ascii_alphabetic()));
+    assert_eq!(rand_uuid!().len(), 36);
+    let n = rand_range!(3, 5);
+    assert!((3..=5).contains(&n));
+}
+
+#[test]
+fn gen_dict_and_rand_dict() {
+    gen_dict!(alpha, 5, into: "RND_WORDS");
+    let words = get_array("RND_WORDS");
+    assert_eq!(words.len(), 5);
+
+    let picked = rand_dict!("RND_WORDS", 3);
+    assert_eq!(picked.split(' ').count(), 3);
+    assert!(picked.split(' ').all(|w| words.contains(&w.to_string())));
+}
+
+#[test]
+fn gen_dict_words_and_syllables() {
+    gen_dict!(syllable, 5, into: "RND_SYL");
+    let words = get_array("RND_SYL");
+    assert_eq!(words.len(), 5);
+    assert!(words.iter().all(|w| w.len() >= 4 && w.chars().all(|c| c.is_ascii_lowercase())));
+
+    gen_dict!(words, 3, into: "RND_BUILTIN");
+    let words = get_array("RND_BUILTIN");
+    assert_eq!(words.len(), 3);
+    assert!(words.iter().all(|w| w.chars().all(|c| c.is_ascii_alphabetic())));
+
+    let dir = assert_fs::TempDir::new().unwrap();
+    let list = format!("{}/words.txt", dir.path().to_str().unwrap());
+    write_file(&list, "alpha\nbeta\ngamma\n");
+    gen_dict!(words, 4, from: list, into: "RND_FROM");
+    let words = get_array("RND_FROM");
+    assert_eq!(words.len(), 4);
+    assert!(words.iter().all(|w| ["alpha", "beta", "gamma"].contains(&w.as_str())));
+}
+
+#[test]
+fn rand_dict_unique_has_no_repeats() {
+    set_array("RND_UNIQ", &["a", "b", "c", "d", "e", "f"]);
+    for _ in 0..20 {
+        let picked: Vec<String> = rand_dict!("RND_UNIQ", 4, unique).split(' ').map(String::from).collect();
+        let mut deduped = picked.clone();
+        deduped.sort();
+        deduped.dedup();
+        assert_eq!(deduped.len(), 4);
+    }
+
+    // Oversized n yields the whole set
+    let mut all: Vec<String> = rand_dict!("RND_UNIQ", 50, unique, ",").split(',').map(String::from).collect();
+    all.sort();
+    assert_eq!(all, vec!["a", "b", "c", "d", "e", "f"]);
+}
@@ -0,0 +1,593 @@
+use rsb::prelude::*;
+
+#[test]
+fn grep_sed_cut_pipeline() {
+    let out = pipe!("ERROR 1.1.1.1 boom\nINFO 2.2.2.2 ok\nERROR 3.3.3.3 bad")
+        .grep("ERROR")
+        .cut(2, " ")
+        .sed(".", "-")
+        .to_vec();
+    assert_eq!(out, vec!["1-1-1-1", "3-3-3-3"]);
+}
+
+#[test]
+fn sort_unique_head_tail() {
+    let s = pipe!("c\na\nb\na\nc");
+    assert_eq!(s.clone().unique().to_vec(), vec!["c", "a", "b"]);
+    assert_eq!(s.clone().sort().uniq().to_vec(), vec!["a", "b", "c"]);
+    assert_eq!(s.clone().head(2).to_vec(), vec!["c", "a"]);
+    assert_eq!(s.tail(2).to_vec(), vec!["a", "c"]);
+}
+
+#[test]
+fn sed_multi_applies_substitutions_in_order() {
+    let template = pipe!("deploy {app} to {env}\nimage {app}:{tag} by {user}");
+    let out = template
+        .sed_multi(&[("{app}", "api"), ("{env}", "prod"), ("{tag}", "v2"), ("{user}", "ci")])
+        .to_vec();
+    assert_eq!(out, vec!["deploy api to prod", "image api:v2 by ci"]);
+
+    // Later pairs see the output of earlier ones
+    assert_eq!(pipe!("a").sed_multi(&[("a", "b"), ("b", "c")]).to_string(), "c");
+
+    let out = pipe!("id=42 name=bob\nid=7 name=al")
+        .sed_re_multi(&[(r"id=(\d+)", "#$1"), (r"name=(\w+)", "<$1>"), ("(", "x"), (r"\s+", " ")])
+        .to_vec();
+    assert_eq!(out, vec!["#42 <bob>", "#7 <al>"]);
+}
+
+#[test]
+fn fields_and_map_fields() {
+    let report = "web   12  ok\ndb  30 ok\n  cache 8   warn";
+    let total: i64 = pipe!(report)
+        .fields("")
+        .iter()
+        .filter_map(|f| f.get(1)?.parse::<i64>().ok())
+        .sum();
+    assert_eq!(total, 50);
+
+    let swapped = pipe!("a,1\nb,2").map_fields(",", |f| format!("{}={}", f[1], f[0])).to_vec();
+    assert_eq!(swapped, vec!["1=a", "2=b"]);
+}
+
+#[test]
+fn head_and_tail_bytes() {
+    let big = "x".repeat(10_000);
+    assert_eq!(pipe!(big.clone()).head_bytes(16).to_string().len(), 16);
+    assert_eq!(pipe!(big).tail_bytes(5).to_string(), "xxxxx");
+    assert_eq!(pipe!("ab\ncd").head_bytes(4).to_vec(), vec!["ab", "c"]);
+    assert_eq!(pipe!("ab\ncd").tail_bytes(100).to_vec(), vec!["ab", "cd"]);
+
+    // "é" and "日" are multi-byte: cuts back off rather than split them
+    let s = "aé日b";
+    assert_eq!(pipe!(s).head_bytes(2).to_string(), "a");
+    assert_eq!(pipe!(s).head_bytes(3).to_string(), "aé");
+    assert_eq!(pipe!(s).head_bytes(5).to_string(), "aé");
+    assert_eq!(pipe!(s).tail_bytes(2).to_string(), "b");
+    assert_eq!(pipe!(s).tail_bytes(4).to_string(), "日b");
+}
+
+#[test]
+fn try_map_collects_errors() {
+    let (parsed, errors) = pipe!("1\ntwo\n3\n4x").try_map(|line| {
+        line.parse::<i64>()
+            .map(|n| (n * 10).to_string())
+            .map_err(|e| format!("{}: {}", line, e))
+    });
+    assert_eq!(parsed.to_vec(), vec!["10", "30"]);
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].starts_with("two: "));
+    assert!(errors[1].starts_with("4x: "));
+}
+
+#[test]
+fn partition_splits_in_one_pass() {
+    let log = "ok 1\nERROR disk\nok 2\nERROR net\nok 3";
+    let (errors, rest) = pipe!(log).partition(|line| line.starts_with("ERROR"));
+    let (errors, rest) = (errors.to_vec(), rest.to_vec());
+    assert_eq!(errors, vec!["ERROR disk", "ERROR net"]);
+    assert_eq!(rest, vec!["ok 1", "ok 2", "ok 3"]);
+
+    let mut all = errors;
+    all.extend(rest);
+    all.sort();
+    let mut original = pipe!(log).to_vec();
+    original.sort();
+    assert_eq!(all, original);
+}
+
+#[test]
+fn from_reader_sources() {
+    let cursor = std::io::Cursor::new(b"alpha\nbeta\r\ngamma".to_vec());
+    assert_eq!(Stream::from_reader(cursor).to_vec(), vec!["alpha", "beta", "gamma"]);
+
+    let bytes: &[u8] = b"one\ntwo\n";
+    assert_eq!(Stream::from_reader(bytes).grep("tw").to_vec(), vec!["two"]);
+
+    let buffered = std::io::BufReader::new(&b"x\ny\nz\n"[..]);
+    assert_eq!(Stream::from_buf_reader(buffered).count(), 3);
+}
+
+#[test]
+fn tee_to_terminal_passes_data_through() {
+    let (rest, out, err) = rsb::utils::capture(|| {
+        let s = tee!(pipe!("{red}build{reset}\nok"), stderr);
+        tee!(s.grep("ok"), stdout).to_vec()
+    });
+    assert_eq!(rest, vec!["ok"]);
+    assert_eq!(err, "\x1b[31mbuild\x1b[0m\nok\n");
+    assert_eq!(out, "ok\n");
+
+    let dir = assert_fs::TempDir::new().unwrap();
+    let log = format!("{}/tee.log", dir.path().to_str().unwrap());
+    assert_eq!(tee!(pipe!("a\nb"), log).count(), 2);
+    assert_eq!(read_file(&log), "a\nb");
+}
+
+#[test]
+fn byte_and_line_sizes() {
+    let s = pipe!("abc\né\n\nxy");
+    assert_eq!(s.line_len(), 4);
+    assert_eq!(s.byte_len(), 10); // 3 + 2 + 0 + 2 bytes of text, 3 separators
+    assert_eq!(s.byte_len(), s.clone().to_string().len());
+    assert_eq!(Stream::new().byte_len(), 0);
+}
+
+#[test]
+fn unique_last_keeps_latest() {
+    assert_eq!(pipe!("a\nb\na\nc\nb").unique_last().to_vec(), vec!["a", "c", "b"]);
+
+    let records = "web,v1\ndb,v1\nweb,v2\ncache,v1\ndb,v3";
+    assert_eq!(
+        pipe!(records).unique_last_by(1, ",").to_vec(),
+        vec!["web,v2", "cache,v1", "db,v3"]
+    );
+}
+
+#[test]
+fn gutter_and_grep_context() {
+    assert_eq!(
+        pipe!("a\nb").with_gutter(10),
+        vec![(10, "a".to_string()), (11, "b".to_string())]
+    );
+
+    let s = "ERR first\n2\n3\n4\n5\nERR mid\n7\n8\nERR last";
+    // Windows clipped at both ends of the input, separate groups split by --
+    assert_eq!(
+        pipe!(s).grep_context("ERR", 2, 1).to_vec(),
+        vec!["ERR first", "2", "--", "4", "5", "ERR mid", "7", "8", "ERR last"]
+    );
+    assert_eq!(pipe!(s).grep_context("first", 3, 0).to_vec(), vec!["ERR first"]);
+    assert_eq!(pipe!(s).grep_context("last", 0, 5).to_vec(), vec!["ERR last"]);
+    assert!(pipe!(s).grep_context("nope", 1, 1).is_empty());
+}
+
+#[test]
+fn write_file_opts_controls_dirs_and_append() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let nested = format!("{}/deep/er/out.txt", dir.path().to_str().unwrap());
+
+    let err = pipe!("x").write_file_opts(&nested, &WriteOpts::default()).unwrap_err();
+    assert!(matches!(err, RsbError::Io { .. }));
+    assert!(!std::path::Path::new(&nested).exists());
+
+    let opts = WriteOpts { create_dirs: true, mode: Some(0o600), ..Default::default() };
+    pipe!("one").write_file_opts(&nested, &opts).unwrap();
+    let append = WriteOpts { append: true, ..opts };
+    pipe!("two").write_file_opts(&nested, &append).unwrap();
+    assert_eq!(read_file(&nested), "one\ntwo\n");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&nested).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    // Without append the file is truncated
+    pipe!("three").write_file_opts(&nested, &opts).unwrap();
+    assert_eq!(read_file(&nested), "three\n");
+}
+
+#[test]
+fn line_ending_conversion() {
+    let crlf = "one\r\ntwo\r\nthree\r";
+    let raw = Stream::from_delimited_string(crlf, "\n");
+    assert_eq!(raw.clone().to_vec(), vec!["one\r", "two\r", "three\r"]);
+
+    let unix = raw.dos2unix();
+    assert_eq!(unix.clone().to_string(), "one\ntwo\nthree");
+
+    let dos = unix.unix2dos().unix2dos();
+    assert_eq!(dos.clone().to_string(), crlf);
+    assert_eq!(dos.dos2unix().to_vec(), vec!["one", "two", "three"]);
+}
+
+#[test]
+fn flatten_json_to_paths() {
+    let config = r#"{
+        "name": "api",
+        "server": { "port": 8080, "tls": true, "hosts": ["a", "b"] },
+        "tags": [],
+        "owner": null,
+        "steps": [{ "run": "make" }]
+    }"#;
+    assert_eq!(
+        pipe!(config).flatten_json().to_vec(),
+        vec![
+            "name=api",
+            "owner=null",
+            "server.hosts[0]=a",
+            "server.hosts[1]=b",
+            "server.port=8080",
+            "server.tls=true",
+            "steps[0].run=make",
+            "tags=[]",
+        ]
+    );
+    assert_eq!(pipe!(config).flatten_json().grep("port").to_vec(), vec!["server.port=8080"]);
+    assert!(pipe!("{oops").flatten_json().is_empty());
+}
+
+#[test]
+fn checksum_sinks_match_known_vectors() {
+    // FIPS 180-2 / RFC 1321 test vectors for "abc"
+    let s = pipe!("abc");
+    assert_eq!(s.sha256(), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    assert_eq!(s.md5(), "900150983cd24fb0d6963f7d28e17f72");
+    assert_eq!(pipe!("a\nb").sha256(), rsb::fs::hash_string("a\nb", "sha256"));
+}
+
+#[test]
+fn expand_vars_per_line() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let tpl = format!("{}/motd.tpl", dir.path().to_str().unwrap());
+    write_file(&tpl, "home=$HOME\nuser=${SXV_USER}\ncost=\\$5\n");
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/root".to_string());
+    set_var("HOME", &home);
+    set_var("SXV_USER", "ada");
+
+    let out = cat!(&tpl).expand_vars().grep("=").to_vec();
+    assert_eq!(out, vec![format!("home={}", home), "user=ada".into(), "cost=$5".into()]);
+}
+
+#[test]
+fn sed_first_vs_global() {
+    let input = "a-b-c\nx-y";
+    assert_eq!(pipe!(input).sed_first("-", "+").to_string(), "a+b-c\nx+y");
+    assert_eq!(pipe!(input).sed("-", "+").to_string(), "a+b+c\nx+y");
+
+    assert_eq!(sed_replace!("a-b-c", "-", "+"), "a+b-c");
+    assert_eq!(sed_replace!("a-b-c", "-", "+", all), "a+b+c");
+}
+
+#[test]
+fn split_sections_on_separators() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let path = format!("{}/records.txt", dir.path().to_str().unwrap());
+    write_file(&path, "---\nname: a\nsize: 1\n---\nname: b\n---\n---\nname: c\nsize: 3\n");
+
+    let sections = cat!(&path).split_sections("^---$");
+    assert_eq!(sections.len(), 3);
+    let sections: Vec<Vec<String>> = sections.into_iter().map(Stream::to_vec).collect();
+    assert_eq!(sections[0], vec!["name: a", "size: 1"]);
+    assert_eq!(sections[1], vec!["name: b"]);
+    assert_eq!(sections[2], vec!["name: c", "size: 3"]);
+
+    let commits = pipe!("commit 1\nfix\ncommit 2\nadd\ntest").split_sections_keep("^commit ");
+    let commits: Vec<String> = commits.into_iter().map(|s| s.to_string()).collect();
+    assert_eq!(commits, vec!["commit 1\nfix", "commit 2\nadd\ntest"]);
+}
+
+#[test]
+fn numeric_reducers_skip_non_numbers() {
+    let sizes = pipe!("10\n2.5\ntotal\n\n-4\n");
+    assert_eq!(sizes.sum_numeric(0, ""), 8.5);
+    assert_eq!(sizes.min_numeric(0, ""), Some(-4.0));
+    assert_eq!(sizes.max_numeric(0, ""), Some(10.0));
+    assert_eq!(sizes.avg_numeric(0, ""), Some(8.5 / 3.0));
+
+    let du = pipe!("4096  ./src\nn/a  ./broken\n1024  ./docs");
+    assert_eq!(du.sum_numeric(1, ""), 5120.0);
+    assert_eq!(pipe!("a,3\nb,x\nc,5").max_numeric(2, ","), Some(5.0));
+
+    let words = pipe!("none\nhere");
+    assert_eq!(words.sum_numeric(0, ""), 0.0);
+    assert_eq!(words.min_numeric(0, ""), None);
+    assert_eq!(words.avg_numeric(0, ""), None);
+}
+
+#[test]
+fn transpose_swaps_rows_and_columns() {
+    let grid = pipe!("a,b,c\n1,2,3").transpose(",");
+    assert_eq!(grid.to_vec(), vec!["a,1", "b,2", "c,3"]);
+
+    let ragged = pipe!("x y z\n1\n7 8").transpose("");
+    assert_eq!(ragged.to_vec(), vec!["x 1 7", "y  8", "z  "]);
+    assert!(pipe!("").transpose(",").is_empty());
+}
+
+#[test]
+fn delete_lines_by_range_pattern_and_block() {
+    let text = "one\ntwo\nthree\nfour\nfive";
+    assert_eq!(pipe!(text).delete_range(2, 3).to_vec(), vec!["one", "four", "five"]);
+    assert_eq!(pipe!(text).delete_range(4, 99).to_vec(), vec!["one", "two", "three"]);
+    assert_eq!(pipe!(text).delete_range(0, 2).count(), 5);
+
+    assert_eq!(pipe!(text).delete_matching("^t").to_vec(), vec!["one", "four", "five"]);
+
+    let config = "a=1\n# BEGIN rsb\nx=1\ny=2\n# END rsb\nb=2\n# BEGIN rsb\nz=3";
+    let cleaned = pipe!(config).delete_block("^# BEGIN rsb", "^# END rsb");
+    assert_eq!(cleaned.to_vec(), vec!["a=1", "b=2"]);
+}
+
+#[test]
+fn streams_collect_from_iterators() {
+    let squares = Stream::from_iter((1..=4).map(|n| format!("{}^2={}", n, n * n)));
+    assert_eq!(squares.to_vec(), vec!["1^2=1", "2^2=4", "3^2=9", "4^2=16"]);
+
+    let names = vec!["b".to_string(), "a".to_string(), "b".to_string()];
+    assert_eq!(stream!(lines: names).sort().unique().to_string(), "a\nb");
+
+    let collected: Stream = ["x", "y"].into_iter().collect();
+    assert_eq!(collected.count(), 2);
+    assert!(stream!(lines: Vec::<String>::new()).is_empty());
+}
+
+#[test]
+fn render_template_fills_placeholders() {
+    let template = "deploy {{PROJECT}} v{{VERSION}}\nenv: {{ ENV }} ({{REGION}})\nraw {braces} stay";
+    let vars: std::collections::HashMap<String, String> = [("PROJECT", "rsb"), ("VERSION", "{{ENV}}"), ("ENV", "prod")]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+    // One pass: a value that looks like a token isn't expanded again
+    assert_eq!(
+        pipe!(template).render_template(&vars).to_vec(),
+        vec!["deploy rsb v{{ENV}}", "env: prod ({{REGION}})", "raw {braces} stay"]
+    );
+    assert_eq!(pipe!(template).render_template_blank(&vars).to_vec()[1], "env: prod ()");
+
+    set_var("TPL_CTX_NAME", "ctx");
+    assert_eq!(pipe!("hi {{TPL_CTX_NAME}} {{TPL_CTX_NONE}}").render_template_ctx().to_string(), "hi ctx {{TPL_CTX_NONE}}");
+}
+
+#[test]
+fn each_throttled_caps_the_rate() {
+    let mut seen = Vec::new();
+    let started = std::time::Instant::now();
+    pipe!("a\nb\nc\nd\ne").each_throttled(20.0, |line| seen.push(line.to_string()));
+    // 5 calls at 20/s leave 4 gaps of 50ms
+    assert!(started.elapsed() >= std::time::Duration::from_millis(200));
+    assert_eq!(seen, vec!["a", "b", "c", "d", "e"]);
+
+    let mut indexed = Vec::new();
+    pipe!("x\ny").each_with_index(|i, line| indexed.push(format!("{}:{}", i, line)));
+    assert_eq!(indexed, vec!["0:x", "1:y"]);
+}
+
+#[test]
+fn sed_lines_and_around() {
+    let s = "1\n2\n3\nERR\n5\n6";
+    assert_eq!(pipe!(s).sed_lines(2, 3).to_string(), "2\n3");
+    assert_eq!(pipe!(s).sed_around("ERR", 1).to_string(), "3\nERR\n5");
+}
+
+#[test]
+fn file_sinks_and_sources() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let path = dir.path().join("out.txt");
+    let path = path.to_str().unwrap();
+
+    pipe!("one\ntwo").to_file(path);
+    pipe!("three").append_to_file(path);
+    assert_eq!(cat!(path).count(), 3);
+    assert_eq!(cat!(path, path).count(), 6);
+}
+
+#[test]
+fn pipe_to_cmd_round_trips() {
+    let out = pipe!("b\na").pipe_to_cmd("sort").to_vec();
+    assert_eq!(out, vec!["a", "b"]);
+}
+
+#[test]
+fn streamable_apply() {
+    let out = pipe!("hi\nthere").apply::<ToUpper>(()).to_vec();
+    assert_eq!(out, vec!["HI", "THERE"]);
+    assert_eq!(Base64Decode::stream_apply(&Base64Encode::stream_apply("rsb", ()), ()), "rsb");
+}
+
+#[test]
+fn diff_marks_added_and_removed_lines() {
+    let before = pipe!("alpha\nbeta\ngamma");
+    let after = pipe!("alpha\ngamma\ndelta");
+    let diff = before.clone().diff(&after).to_vec();
+
+    assert_eq!(diff[0], "--- a");
+    assert_eq!(diff[1], "+++ b");
+    assert!(diff.contains(&"-beta".to_string()));
+    assert!(diff.contains(&"+delta".to_string()));
+    assert!(diff.contains(&" alpha".to_string()));
+    assert!(before.clone().diff(&before).is_empty());
+}
+
+#[test]
+fn diff_files_produces_unified_output() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let a = dir.path().join("a.txt");
+    let b = dir.path().join("b.txt");
+    let (a, b) = (a.to_str().unwrap(), b.to_str().unwrap());
+    write_file(a, "one\ntwo\n");
+    write_file(b, "one\n2\n");
+
+    let out = diff!(files: a, b);
+    assert!(out.contains(&format!("--- {}", a)));
+    assert!(out.contains("@@ -1,2 +1,2 @@"));
+    assert!(out.contains("-two\n+2\n"));
+    assert_eq!(diff!("same\n", "same\n"), "");
+}
+
+#[test]
+fn counting_and_predicate_sinks() {
+    let s = pipe!("ERROR a\nINFO b\nERROR c\nWARN d");
+    assert_eq!(s.count_matching("ERROR"), 2);
+    assert_eq!(s.count_matching("DEBUG"), 0);
+
+    assert!(s.any(|l| l.starts_with("WARN")));
+    assert!(!s.any(|l| l.is_empty()));
+    assert!(s.all(|l| l.contains(' ')));
+    assert!(!s.all(|l| l.starts_with("ERROR")));
+
+    // all() short-circuits on the first failure
+    let seen = std::cell::Cell::new(0);
+    s.all(|l| {
+        seen.set(seen.get() + 1);
+        l.starts_with("ERROR")
+    });
+    assert_eq!(seen.get(), 2);
+    assert!(Stream::new().all(|_| false));
+}
+
+#[test]
+fn count_unique_orders_by_frequency() {
+    let s = pipe!("timeout\nrefused\ntimeout\ndisk full\nrefused\ntimeout\nauth");
+    assert_eq!(
+        s.count_unique(),
+        vec![
+            (3, "timeout".to_string()),
+            (2, "refused".to_string()),
+            (1, "disk full".to_string()),
+            (1, "auth".to_string()),
+        ]
+    );
+    assert!(Stream::new().count_unique().is_empty());
+}
+
+#[test]
+fn jsonl_records_filter_and_round_trip() {
+    let input = r#"{"level":"error","msg":"disk full"}
+{"level":"info","msg":"started"}
+not json at all
+{"level":"error","msg":"timeout"}"#;
+
+    let records = pipe!(input).parse_json();
+    assert_eq!(records.len(), 3);
+
+    let errors: Vec<_> = records
+        .into_iter()
+        .filter(|r| r["level"] == "error")
+        .collect();
+    let out = Stream::from_json_lines(&errors);
+    assert_eq!(
+        out.clone().to_vec(),
+        vec![
+            r#"{"level":"error","msg":"disk full"}"#,
+            r#"{"level":"error","msg":"timeout"}"#,
+        ]
+    );
+    assert_eq!(out.parse_json()[1]["msg"], "timeout");
+}
+
+#[test]
+fn csv_rows_honour_quoting() {
+    let data = "name,city,notes\n\"Smith, Jo\",Leeds,\"line one\nline two\"\nAli,York,plain";
+    let rows = pipe!(data).csv_rows(true);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["name"], "Smith, Jo");
+    assert_eq!(rows[0]["notes"], "line one\nline two");
+    assert_eq!(rows[1]["city"], "York");
+
+    let raw = pipe!("a,b\nc,\"d,e\"").csv_rows(false);
+    assert_eq!(raw[1]["1"], "d,e");
+}
+
+#[test]
+fn csv_select_projects_columns() {
+    let data = "name,city,age\n\"Smith, Jo\",Leeds,41\nAli,York,29";
+    let out = pipe!(data).csv_select(&["age", "name"]).to_vec();
+    assert_eq!(out, vec!["age,name", "41,\"Smith, Jo\"", "29,Ali"]);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn par_map_preserves_order() {
+    let input: Vec<String> = (0..2_000).map(|i| format!("line {}", i)).collect();
+    let work = |line: &str| format!("{}:{}", line.len(), line.to_uppercase());
+
+    let sequential = Stream::from_vec(&input).map(work).to_vec();
+    let parallel = Stream::from_vec(&input).par_map(work).to_vec();
+    assert_eq!(parallel, sequential);
+}
+
+#[cfg(feature = "clipboard")]
+#[test]
+fn clipboard_round_trip() {
+    // Headless CI has no clipboard: that must be an error, not a panic
+    let copied = match pipe!("one\ntwo").to_clipboard() {
+        Ok(()) => true,
+        Err(e) => {
+            assert!(e.to_string().starts_with("Clipboard unavailable"));
+            eprintln!("skipping: {}", e);
+            false
+        }
+    };
+    if copied {
+        assert_eq!(Stream::from_clipboard().unwrap().to_vec(), vec!["one", "two"]);
+    }
+}
+
+#[test]
+fn cat_expands_globs_in_sorted_order() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let base = dir.path().to_str().unwrap().to_string();
+    write_file(&format!("{}/b.txt", base), "two\n");
+    write_file(&format!("{}/a.txt", base), "one\n");
+    write_file(&format!("{}/c.txt", base), "three\n");
+    write_file(&format!("{}/skip.log", base), "nope\n");
+
+    let pattern = format!("{}/*.txt", base);
+    assert_eq!(cat!(&pattern).to_vec(), vec!["one", "two", "three"]);
+
+    let log = format!("{}/skip.log", base);
+    assert_eq!(cat!(&log, &format!("{}/?.txt", base)).count(), 4);
+
+    // A glob with no matches contributes nothing
+    assert!(cat!(&format!("{}/*.csv", base)).is_empty());
+}
+
+#[test]
+fn inspect_passes_data_through() {
+    let s = pipe!("a\nb\nc\nd");
+    assert_eq!(s.clone().inspect("label"), s);
+    set_var("DEBUG_MODE", "1");
+    assert_eq!(s.clone().inspect("label").to_vec(), vec!["a", "b", "c", "d"]);
+    unset_var("DEBUG_MODE");
+}
+
+#[test]
+fn replace_lines_by_range() {
+    let s = pipe!("1\n2\n3\n4\n5");
+    assert_eq!(
+        s.clone().replace_lines(2, 4, "two\nthree").to_vec(),
+        vec!["1", "two", "three", "5"]
+    );
+    assert_eq!(s.clone().replace_lines(5, 5, "").to_vec(), vec!["1", "2", "3", "4"]);
+
+    // End past the last line is clamped
+    assert_eq!(s.clone().replace_lines(4, 99, "end").to_vec(), vec!["1", "2", "3", "end"]);
+
+    // Ranges that start out of bounds change nothing
+    assert_eq!(s.clone().replace_lines(6, 8, "x"), s);
+    assert_eq!(s.clone().replace_lines(0, 2, "x"), s);
+    assert_eq!(s.clone().replace_lines(3, 2, "x"), s);
+}
+
+#[test]
+fn split_on_flattens_fields() {
+    let out = pipe!("a,b,c\nd\ne,f").split_on(",").to_vec();
+    assert_eq!(out, vec!["a", "b", "c", "d", "e", "f"]);
+    assert_eq!(pipe!("x::y").split_on("::").to_vec(), vec!["x", "y"]);
+    assert_eq!(pipe!("a,,b").split_on(",").count(), 3);
+}
@@ -0,0 +1,56 @@
+use rsb::prelude::*;
+
+#[test]
+fn sleep_until_waits_for_the_deadline() {
+    let target = current_epoch_ms() + 150;
+    sleep_until(target);
+    assert!(current_epoch_ms() >= target);
+    // A deadline in the past returns immediately
+    sleep_until(0);
+}
+
+// One test so the SIGINT case can't cut a concurrent loop short
+#[cfg(unix)]
+#[test]
+fn until_runs_to_deadline_and_stops_on_sigint() {
+    let start = std::time::Instant::now();
+    let mut runs = 0;
+    let iterations = until!(1, every: 200 => {
+        runs += 1;
+    });
+    assert_eq!(iterations, runs);
+    assert!((4..=6).contains(&runs), "ran {} times", runs);
+    assert!(start.elapsed().as_millis() >= 900);
+
+    let start = std::time::Instant::now();
+    let iterations = until!(10, every: 100 => {
+        // SAFETY: raising SIGINT at ourselves while until! has a handler installed
+        unsafe { rsb::deps::libc::raise(rsb::deps::libc::SIGINT) };
+    });
+    assert_eq!(iterations, 1);
+    assert!(start.elapsed().as_secs() < 2);
+}
+
+#[test]
+fn with_timeout_returns_value_or_none() {
+    let fast = with_timeout!(2 => { 21 * 2 });
+    assert_eq!(fast, Some(42));
+
+    let start = std::time::Instant::now();
+    let slow = with_timeout!(0.2 => {
+        std::thread::sleep(std::time::Duration::from_secs(3));
+        "done"
+    });
+    assert_eq!(slow, None);
+    assert!(start.elapsed().as_secs() < 2);
+}
+
+#[test]
+fn time_it_returns_value_and_duration() {
+    let (value, elapsed) = time_it!({
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        6 * 7
+    });
+    assert_eq!(value, 42);
+    assert!(elapsed >= std::time::Duration::from_millis(20));
+}
@@ -0,0 +1,333 @@
+use rsb::prelude::*;
+
+#[test]
+fn arrays_round_trip_through_context() {
+    set_array("UT_ARR", &["one", "two"]);
+    array_push("UT_ARR", "three");
+    assert_eq!(get_array("UT_ARR"), vec!["one", "two", "three"]);
+    assert_eq!(array_length("UT_ARR"), 3);
+    assert_eq!(array_get("UT_ARR", 1), "two");
+    assert!(array_contains("UT_ARR", "three"));
+}
+
+#[test]
+fn str_explode_splits_into_array() {
+    str_explode!("a,b,c", on: ",", into: "UT_EXPLODE");
+    assert_eq!(get_array("UT_EXPLODE"), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn param_expansions() {
+    set_var("UT_PATH", "/var/log/app.log.gz");
+    assert_eq!(param!("UT_PATH", prefix: "*/"), "var/log/app.log.gz");
+    assert_eq!(param!("UT_PATH", prefix: "*/", longest), "app.log.gz");
+    assert_eq!(param!("UT_PATH", suffix: ".*"), "/var/log/app.log");
+    assert_eq!(param!("UT_PATH", suffix: ".*", longest), "/var/log/app");
+    assert_eq!(param!("UT_PATH", sub: 1, 3), "var");
+    assert_eq!(param!("UT_PATH", replace: "/" => "_"), "_var/log/app.log.gz");
+    assert_eq!(param!("UT_PATH", replace: "/" => "_", all), "_var_log_app.log.gz");
+    assert_eq!(param!("UT_UNSET", default: "fallback"), "fallback");
+    assert_eq!(param!("UT_PATH", alt: "set"), "set");
+    assert_eq!(param!("UT_PATH", len), 19);
+}
+
+#[test]
+fn case_conversion() {
+    set_var("UT_WORD", "hello World");
+    assert_eq!(param!("UT_WORD", upper), "HELLO WORLD");
+    assert_eq!(param!("UT_WORD", upper: first), "Hello World");
+    assert_eq!(param!("UT_WORD", lower), "hello world");
+}
+
+#[test]
+fn test_macro_conditions() {
+    assert!(test!(-n "x"));
+    assert!(test!(-z ""));
+    assert!(test!("a", ==, "a"));
+    assert!(test!("v1.2", =~, r"^v\d+\.\d+$"));
+    assert!(test!(10, -gt, 9));
+    assert!(test!("3", -le, "3"));
+    assert!(!test!("abc", -lt, "5"));
+}
+
+#[test]
+fn expand_colors_replaces_known_tags() {
+    let out = expand_colors("{red}x{reset} {notacolor}");
+    assert!(out.starts_with("\x1b[31mx\x1b[0m"));
+    assert!(out.ends_with("{notacolor}"));
+}
+
+#[test]
+fn check_reports_without_exiting() {
+    set_var("UT_CHECK_SET", "1");
+    assert!(check!(var: "UT_CHECK_SET"));
+    assert!(!check!(var: "UT_CHECK_MISSING"));
+    assert!(!check!(1 > 2, "one is not greater than {}", 2));
+}
+
+#[test]
+fn str_join_inverts_str_explode() {
+    let original = "red, green, blue";
+    str_explode!(original, on: ", ", into: "UT_JOIN");
+    assert_eq!(array_length("UT_JOIN"), 3);
+    assert_eq!(str_join!("UT_JOIN", with: ", "), original);
+    assert_eq!(str_join!("UT_JOIN", with: "|"), "red|green|blue");
+}
+
+#[test]
+fn base64_and_hex_round_trip() {
+    let text = "rsb ✓ strings";
+    assert_eq!(b64!("hello"), "aGVsbG8=");
+    assert_eq!(b64!(decode: b64!(text)), text);
+    assert_eq!(hex!("hi"), "6869");
+    assert_eq!(hex!(decode: hex!(text)), text);
+    assert_eq!(base64_decode(&base64_encode(text)).unwrap(), text);
+    assert_eq!(hex_decode(&hex_encode(text)).unwrap(), text);
+}
+
+#[test]
+fn invalid_decode_returns_original_or_error() {
+    assert_eq!(b64!(decode: "not base64!"), "not base64!");
+    assert!(b64!(decode: "not base64!", strict).is_err());
+    assert_eq!(hex!(decode: "xyz"), "xyz");
+    assert!(hex!(decode: "abc", strict).is_err());
+    assert!(hex!(decode: "zz", strict).is_err());
+    assert!(hex!(decode: "ff", strict).is_err());
+}
+
+#[test]
+fn padding_is_display_width_aware() {
+    assert_eq!(str_pad!("7", left: 3, fill: '0'), "007");
+    assert_eq!(str_pad!("ab", right: 4), "ab  ");
+    assert_eq!(str_pad!("toolong", left: 3), "toolong");
+    assert_eq!(str_pad!("日本", right: 6, fill: '.'), "日本..");
+
+    let red = "\x1b[31mok\x1b[0m";
+    assert_eq!(str_pad!(red, left: 4), format!("  {}", red));
+    assert_eq!(display_width!(str_pad!(red, right: 5)), 5);
+}
+
+#[test]
+fn centering_splits_padding() {
+    assert_eq!(str_center!("ab", 6), "  ab  ");
+    assert_eq!(str_center!("ab", 5), " ab  ");
+    assert_eq!(str_center!("日", 4), " 日 ");
+}
+
+#[test]
+fn truncation_respects_width_and_ansi() {
+    assert_eq!(str_truncate!("hello world", 8), "hello w…");
+    assert_eq!(str_truncate!("hello world", 8, ellipsis: "..."), "hello...");
+    assert_eq!(str_truncate!("short", 8), "short");
+    assert_eq!(str_truncate!("日本語テキスト", 7), "日本語…");
+
+    let out = str_truncate!("\x1b[32mgreen text\x1b[0m", 6);
+    assert_eq!(out, "\x1b[32mgreen\x1b[0m…");
+    assert_eq!(display_width!(out), 6);
+}
+
+#[test]
+fn str_matches_behaviour_is_unchanged() {
+    assert!(str_matches("v1.2.3", r"^v\d+\.\d+\.\d+$"));
+    assert!(!str_matches("1.2.3", r"^v\d+"));
+    // Invalid patterns never match, cached or not
+    assert!(!str_matches("anything", "("));
+    assert!(!str_matches("anything", "("));
+    for _ in 0..3 {
+        assert!(str_matches("ERROR 42", r"ERROR \d+"));
+    }
+}
+
+// Recompiling the pattern per line vs. the cached path over the same stream
+#[test]
+fn str_matches_cache_beats_recompiling() {
+    use rsb::deps::regex::Regex;
+    use std::time::Instant;
+
+    let pattern = r"(?i)^(error|warn)\s+\[[a-z_]+\]\s+\d{3,}";
+    let lines: Vec<String> = (0..1_000)
+        .map(|i| format!("{} [svc_{}] {}", if i % 3 == 0 { "ERROR" } else { "INFO" }, i % 7, i))
+        .collect();
+
+    let start = Instant::now();
+    let fresh = lines
+        .iter()
+        .filter(|l| Regex::new(pattern).unwrap().is_match(l))
+        .count();
+    let uncached = start.elapsed();
+
+    let start = Instant::now();
+    let cached = pipe!(lines.join("\n")).grep_re(pattern).count();
+    let with_cache = start.elapsed();
+
+    assert_eq!(fresh, cached);
+    println!("uncached: {:?}, cached: {:?}", uncached, with_cache);
+    assert!(with_cache * 5 < uncached);
+}
+
+#[test]
+fn substring_offsets() {
+    set_var("UT_SUB", "release-1.42");
+    assert_eq!(param!("UT_SUB", sub: 8), "1.42");
+    assert_eq!(param!("UT_SUB", sub: 0, 7), "release");
+    assert_eq!(param!("UT_SUB", sub: -4), "1.42");
+    assert_eq!(param!("UT_SUB", sub: -4, 1), "1");
+    assert_eq!(param!("UT_SUB", sub: -12), "release-1.42");
+
+    // Out of range, like bash
+    assert_eq!(param!("UT_SUB", sub: 40), "");
+    assert_eq!(param!("UT_SUB", sub: -13), "");
+    assert_eq!(str_sub("日本語", -2, None), "本語");
+}
+
+#[test]
+fn heredoc_expands_and_strips_indentation() {
+    set_var("UT_HD_APP", "rebel");
+    set_var("UT_HD_PORT", "8080");
+
+    let text = heredoc!(strip: r#"
+        [server]
+        name = "$UT_HD_APP"
+          port = ${UT_HD_PORT}
+        "#);
+    assert_eq!(text, "[server]\nname = \"rebel\"\n  port = 8080\n");
+
+    assert_eq!(heredoc!("  keep $UT_HD_APP\n"), "  keep rebel\n");
+}
+
+#[test]
+fn json_builder_escapes_values() {
+    let token = String::from("ab\"c\nd");
+    let body = json!({ "token" => token, "status" => "ok", "count" => 3, "ok" => true });
+    let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(parsed["token"], "ab\"c\nd");
+    assert_eq!(parsed["status"], "ok");
+    assert_eq!(parsed["count"], 3);
+    assert_eq!(parsed["ok"], true);
+    assert_eq!(json!({}), "{}");
+}
+
+#[test]
+fn array_set_operations() {
+    set_array("SET_INSTALLED", &["git", "curl", "jq", "git"]);
+    set_array("SET_REQUIRED", &["jq", "make", "git", "rg"]);
+
+    assert_eq!(array_union!("SET_INSTALLED", "SET_REQUIRED"), vec!["git", "curl", "jq", "make", "rg"]);
+    assert_eq!(array_intersect!("SET_INSTALLED", "SET_REQUIRED"), vec!["git", "jq"]);
+    assert_eq!(array_difference!("SET_INSTALLED", "SET_REQUIRED"), vec!["curl"]);
+    assert_eq!(array_difference!("SET_REQUIRED", "SET_INSTALLED"), vec!["make", "rg"]);
+
+    array_difference!("SET_REQUIRED", "SET_INSTALLED", into: "SET_MISSING");
+    assert_eq!(get_array("SET_MISSING"), vec!["make", "rg"]);
+}
+
+#[test]
+fn byte_size_formatting_and_parsing() {
+    assert_eq!(bytes!(0), "0 B");
+    assert_eq!(bytes!(1023), "1023 B");
+    assert_eq!(bytes!(1024), "1.0 KiB");
+    assert_eq!(bytes!(1536 * 1024), "1.5 MiB");
+    assert_eq!(bytes!(1000, si), "1.0 KB");
+    assert_eq!(bytes!(999, si), "999 B");
+    assert_eq!(bytes!(u64::MAX), "16.0 EiB");
+
+    assert_eq!(bytes!(parse: "512"), Ok(512));
+    assert_eq!(bytes!(parse: "1.5MB"), Ok(1_500_000));
+    assert_eq!(bytes!(parse: "1.5 MiB"), Ok(1_572_864));
+    assert_eq!(bytes!(parse: "10k"), Ok(10_240));
+    assert_eq!(bytes!(parse: "2 GB"), Ok(2_000_000_000));
+    assert_eq!(bytes!(parse: "7B"), Ok(7));
+    assert!(bytes!(parse: "12 parsecs").is_err());
+    assert!(bytes!(parse: "MB").is_err());
+}
+
+#[test]
+fn aggregated_requirements() {
+    let (ok, _, err) = rsb::utils::capture(|| check_all!(var: "REQ_A", var: "REQ_B", file: "/no/such/file"));
+    assert!(!ok);
+    assert_eq!(err.lines().count(), 1);
+    assert!(err.contains("missing variable REQ_A, variable REQ_B, file /no/such/file"));
+
+    let (ok, _, err) = rsb::utils::capture(|| check_any!(var: "REQ_A", var: "REQ_B"));
+    assert!(!ok);
+    assert!(err.contains("need at least one of variable REQ_A, variable REQ_B"));
+
+    set_var("REQ_B", "token");
+    let (ok, _, err) = rsb::utils::capture(|| check_any!(var: "REQ_A", var: "REQ_B"));
+    assert!(ok && err.is_empty());
+    let (ok, _, err) = rsb::utils::capture(|| check_all!(var: "REQ_A", var: "REQ_B"));
+    assert!(!ok);
+    assert!(err.contains("missing variable REQ_A") && !err.contains("REQ_B"));
+
+    // Satisfied requirements don't exit
+    require_any!(var: "REQ_A", var: "REQ_B");
+    require_all!(var: "REQ_B", cmd: "sh");
+}
+
+#[test]
+fn quiet_block_suppresses_echo() {
+    let (value, out, err) = rsb::utils::capture(|| {
+        echo!("before");
+        let inner = quiet! {
+            echo!("hidden");
+            printf!("hidden too");
+            info!("hidden info");
+            error!("still shown");
+            5
+        };
+        echo!("after");
+        inner
+    });
+    assert_eq!(value, 5);
+    assert_eq!(out, "before\nafter\n");
+    assert!(err.contains("still shown"));
+    assert!(!err.contains("hidden info"));
+}
+
+#[test]
+fn capture_vars_sets_named_groups() {
+    let pattern = r"ver=(?P<major>\d+)\.(?P<minor>\d+)(?:-(?P<pre>\w+))?";
+    assert!(capture_vars!(pattern, in: "app ver=2.14 built", into: "CAPV"));
+    assert_eq!(get_var("CAPV_major"), "2");
+    assert_eq!(get_var("CAPV_minor"), "14");
+    assert_eq!(get_var("CAPV_pre"), "");
+
+    set_var("CAPN_major", "keep");
+    assert!(!capture_vars!(pattern, in: "no version here", into: "CAPN"));
+    assert_eq!(get_var("CAPN_major"), "keep");
+    assert!(!has_var("CAPN_minor"));
+
+    assert!(capture_vars!(r"(?P<CAPB_host>[^:]+):(?P<CAPB_port>\d+)", in: "db.local:5432"));
+    assert_eq!(get_var("CAPB_host"), "db.local");
+    assert_eq!(get_var("CAPB_port"), "5432");
+}
+
+#[test]
+fn colorize_wraps_and_nests() {
+    let (red, blue, reset) = (get_color("red"), get_color("blue"), get_color("reset"));
+    assert!(!red.is_empty() && !reset.is_empty());
+    assert_eq!(hl!("hi", "red"), format!("{}hi{}", red, reset));
+    assert_eq!(colorize("warm", "orange"), "\x1b[38;5;208mwarm".to_string() + &reset);
+    assert_eq!(colorize("plain", "no-such-color"), "plain");
+
+    let nested = hl!(format!("a {} b", hl!("x", "blue")), "red");
+    assert_eq!(nested, format!("{red}a {blue}x{reset}{red} b{reset}"));
+}
+
+#[test]
+fn section_header_spans_the_width() {
+    let header = section_header("Building", 40);
+    assert!(header.contains("Building"));
+    assert_eq!(display_width!(header), 40);
+    assert!(header.starts_with('─') && header.ends_with('─'));
+
+    let odd = section_header("Deploy stage", 31);
+    assert_eq!(display_width!(odd), 31);
+    assert_eq!(display_width!(section_header("a very long title that cannot fit", 12)), 12);
+    assert_eq!(section_header("", 5), "─────");
+
+    let ((), _, err) = rsb::utils::capture(|| section!("Stage {}", 2));
+    assert!(err.contains("Stage 2"));
+    let ((), _, err) = rsb::utils::capture(|| quiet! { section!("Hidden") });
+    assert!(err.is_empty());
+}
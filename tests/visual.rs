@@ -0,0 +1,40 @@
+use rsb::prelude::*;
+
+#[test]
+fn term_width_is_positive_and_sets_cols() {
+    let cols = term_width!();
+    assert!(cols > 0);
+    assert_eq!(get_var("COLS"), cols.to_string());
+}
+
+#[test]
+fn display_width_ignores_ansi() {
+    assert_eq!(display_width!("plain"), 5);
+    assert_eq!(display_width!("\x1b[31mred\x1b[0m"), 3);
+    assert_eq!(display_width!(expand_colors("{bold}{green}ok{reset}")), 2);
+    assert_eq!(display_width!("日本"), 4);
+}
+
+#[test]
+fn theme_presets_swap_role_colors() {
+    let default_error = get_color("red");
+    assert_eq!(default_error, "\x1b[31m");
+
+    assert!(theme!("colorblind_safe"));
+    assert_eq!(get_color("red"), "\x1b[38;5;208m");
+    assert_ne!(get_color("green"), "\x1b[32m");
+
+    assert!(theme!("high_contrast"));
+    assert_eq!(get_color("red"), "\x1b[91m");
+
+    assert!(theme!("minimal"));
+    assert_eq!(get_color("red"), "");
+    assert_eq!(get_glyph("pass"), "+");
+
+    assert!(!theme!("nope"));
+    assert_eq!(get_glyph("pass"), "+");
+
+    assert!(theme!("default"));
+    assert_eq!(get_color("red"), default_error);
+    assert_eq!(get_glyph("pass"), "✓");
+}